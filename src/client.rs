@@ -1,17 +1,31 @@
+mod alertmanager;
+mod alerts;
+mod api;
 mod label_names;
 mod label_names_builder;
 mod label_values;
 mod label_values_builder;
 mod metric_metadata;
 mod parse;
+mod pushgateway;
 mod query_builder;
+mod query_exemplars;
 mod query_instant;
 mod query_range;
+mod response;
+mod rules;
+mod sample_parser;
 mod scrape;
 mod selector_parser;
 mod series;
+mod sources_check;
+mod status;
 mod targets;
+mod tsdb_stats;
 
+pub use alertmanager::{Alertmanager, Silence, is_silence_id};
+pub use alerts::Alerts;
+pub use api::{Annotated, ApiRequest};
 pub use label_names::LabelNames;
 pub use label_names_builder::LabelNamesBuilder;
 pub use label_values::LabelValues;
@@ -20,13 +34,23 @@ pub use metric_metadata::MetricMetadata;
 use nu_protocol::{LabeledError, Span};
 pub use parse::Parse;
 pub use parse::ParseFormat;
+pub use pushgateway::{Grouping, Pushgateway};
 pub use query_builder::QueryBuilder;
+pub use query_exemplars::QueryExemplars;
 pub use query_instant::QueryInstant;
 pub use query_range::QueryRange;
+pub use response::{
+    Data, Histogram, InstantVector, Point, QueryResult, RangeVector, ResultType, Stats,
+    StringSample,
+};
+pub use rules::Rules;
 pub use scrape::Scrape;
-pub use selector_parser::SelectorParser;
+pub use selector_parser::{Matcher, SelectorParser};
 pub use series::Series;
+pub use sources_check::SourcesCheck;
+pub use status::{Status, StatusEndpoint};
 pub use targets::Targets;
+pub use tsdb_stats::TsdbStats;
 
 pub trait Client {
     fn runtime(&self) -> Result<tokio::runtime::Runtime, LabeledError> {
@@ -37,6 +61,25 @@ pub trait Client {
     }
 }
 
+/// The URL of `path` relative to the base URL of `client`
+pub fn api_url(client: &prometheus_http_query::Client, path: &str) -> reqwest::Url {
+    join_url(client.base_url(), path)
+}
+
+/// The URL of `path` relative to `base`, keeping any path prefix of `base`
+pub fn join_url(base: &reqwest::Url, path: &str) -> reqwest::Url {
+    let mut url = base.clone();
+
+    if url.path() == "/" {
+        url.set_path(path);
+    } else {
+        let path = format!("{}/{path}", url.path().trim_end_matches('/'));
+        url.set_path(&path);
+    }
+
+    url
+}
+
 pub fn labeled_error(error: prometheus_http_query::Error, span: Span) -> LabeledError {
     use prometheus_http_query::Error;
 
@@ -0,0 +1,577 @@
+use crate::{
+    Client,
+    client::{Matcher, alerts::alert_record, api::timestamp, join_url},
+    signals::run_with_signal,
+    source::{Service, Source},
+    time::rfc3339_to_value,
+};
+use chrono::{DateTime, FixedOffset};
+use nu_protocol::{
+    IntoInterruptiblePipelineData, LabeledError, PipelineData, Signals, Span, Value, record,
+};
+use reqwest::{RequestBuilder, Url, header::CONTENT_TYPE};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::HashMap;
+
+/// A client for the Alertmanager v2 API of a source
+pub struct Alertmanager {
+    client: reqwest::Client,
+    url: Url,
+}
+
+/// A silence to create
+pub struct Silence {
+    pub matchers: Vec<Matcher>,
+    pub starts_at: DateTime<FixedOffset>,
+    pub ends_at: DateTime<FixedOffset>,
+    pub created_by: String,
+    pub comment: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Alert {
+    labels: HashMap<String, String>,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+    starts_at: String,
+    ends_at: String,
+    fingerprint: String,
+    #[serde(default)]
+    receivers: Vec<Receiver>,
+    status: AlertStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct Receiver {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertStatus {
+    state: String,
+    #[serde(default)]
+    silenced_by: Vec<String>,
+    #[serde(default)]
+    inhibited_by: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SilenceMatcher {
+    name: String,
+    value: String,
+    is_regex: bool,
+    #[serde(default = "default_is_equal")]
+    is_equal: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GettableSilence {
+    id: String,
+    status: SilenceStatus,
+    matchers: Vec<SilenceMatcher>,
+    starts_at: String,
+    ends_at: String,
+    created_by: String,
+    comment: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SilenceStatus {
+    state: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostableSilence {
+    matchers: Vec<SilenceMatcher>,
+    starts_at: String,
+    ends_at: String,
+    created_by: String,
+    comment: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SilenceCreated {
+    #[serde(rename = "silenceID")]
+    silence_id: String,
+}
+
+/// Matchers without `isEqual` come from Alertmanager versions before negative
+/// matchers were supported
+fn default_is_equal() -> bool {
+    true
+}
+
+impl From<Matcher> for SilenceMatcher {
+    fn from(matcher: Matcher) -> Self {
+        Self {
+            name: matcher.name,
+            value: matcher.value,
+            is_regex: matcher.is_regex,
+            is_equal: matcher.is_equal,
+        }
+    }
+}
+
+impl From<SilenceMatcher> for Matcher {
+    fn from(matcher: SilenceMatcher) -> Self {
+        Self {
+            name: matcher.name,
+            value: matcher.value,
+            is_regex: matcher.is_regex,
+            is_equal: matcher.is_equal,
+        }
+    }
+}
+
+impl Alertmanager {
+    pub fn new(source: &Source) -> Result<Self, LabeledError> {
+        let client = source
+            .service_client_builder(Service::Alertmanager)?
+            .build()
+            .map_err(|e| {
+                LabeledError::new("Unable to build alertmanager client").with_help(e.to_string())
+            })?;
+
+        let url = source.service_request_url(Service::Alertmanager)?;
+
+        let url = Url::parse(&url).map_err(|e| {
+            LabeledError::new("Invalid alertmanager URL")
+                .with_label(format!("{url:?}: {e}"), source.span)
+        })?;
+
+        Ok(Self { client, url })
+    }
+
+    /// Lists alerts, optionally only those matching `filter`
+    pub fn alerts(
+        self,
+        filter: Vec<Matcher>,
+        flatten: bool,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<PipelineData, LabeledError> {
+        let request = self.client.get(self.filter_url("api/v2/alerts", &filter));
+
+        self.runtime()?.block_on(async {
+            let alerts: Vec<Alert> = run_with_signal(signals, span, send(request, span)).await??;
+
+            Ok(alerts
+                .into_iter()
+                .map(move |alert| alert_to_value(alert, flatten, span))
+                .into_pipeline_data(span, signals.clone()))
+        })
+    }
+
+    /// Lists silences, optionally only those matching `filter`
+    pub fn silences(
+        self,
+        filter: Vec<Matcher>,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<PipelineData, LabeledError> {
+        let request = self.client.get(self.filter_url("api/v2/silences", &filter));
+
+        self.runtime()?.block_on(async {
+            let silences: Vec<GettableSilence> =
+                run_with_signal(signals, span, send(request, span)).await??;
+
+            Ok(silences
+                .into_iter()
+                .map(move |silence| silence_to_value(silence, span))
+                .into_pipeline_data(span, signals.clone()))
+        })
+    }
+
+    /// Creates `silence`, returning its ID
+    pub fn add_silence(
+        self,
+        silence: Silence,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<Value, LabeledError> {
+        let Silence {
+            matchers,
+            starts_at,
+            ends_at,
+            created_by,
+            comment,
+        } = silence;
+
+        let body = PostableSilence {
+            matchers: matchers.into_iter().map(SilenceMatcher::from).collect(),
+            starts_at: timestamp(starts_at),
+            ends_at: timestamp(ends_at),
+            created_by,
+            comment,
+        };
+
+        let body = serde_json::to_vec(&body).map_err(|e| {
+            LabeledError::new("Alertmanager client error").with_label(e.to_string(), span)
+        })?;
+
+        let request = self
+            .client
+            .post(self.url("api/v2/silences"))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body);
+
+        self.runtime()?.block_on(async {
+            let created: SilenceCreated =
+                run_with_signal(signals, span, send(request, span)).await??;
+
+            let record = record! {
+                "id" => Value::string(created.silence_id, span),
+                "starts_at" => Value::date(starts_at, span),
+                "ends_at" => Value::date(ends_at, span),
+            };
+
+            Ok(Value::record(record, span))
+        })
+    }
+
+    /// Expires the silence `id`
+    pub fn expire_silence(
+        self,
+        id: &str,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<Value, LabeledError> {
+        let request = self.client.delete(self.silence_url(id));
+
+        self.runtime()?.block_on(async {
+            run_with_signal(signals, span, response(request, span)).await??;
+
+            Ok(Value::nothing(span))
+        })
+    }
+
+    fn url(&self, path: &str) -> Url {
+        join_url(&self.url, path)
+    }
+
+    /// The URL of the silence `id`, with `id` encoded as a single path segment
+    fn silence_url(&self, id: &str) -> Url {
+        let mut url = self.url("api/v2/silence");
+
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.push(id);
+        }
+
+        url
+    }
+
+    /// The URL of `path` with a `filter` parameter for each matcher
+    fn filter_url(&self, path: &str, filter: &[Matcher]) -> Url {
+        let mut url = self.url(path);
+
+        if !filter.is_empty() {
+            url.query_pairs_mut()
+                .extend_pairs(filter.iter().map(|matcher| ("filter", matcher.to_string())));
+        }
+
+        url
+    }
+}
+
+impl Client for Alertmanager {}
+
+/// Whether `id` is a silence ID, a UUID like
+/// `0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3c`
+pub fn is_silence_id(id: &str) -> bool {
+    id.len() == 36
+        && id.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Sends `request` and deserializes the JSON response
+async fn send<T: DeserializeOwned>(request: RequestBuilder, span: Span) -> Result<T, LabeledError> {
+    let body = response(request, span).await?;
+
+    serde_json::from_slice(&body).map_err(|e| {
+        LabeledError::new("Alertmanager client error")
+            .with_label(format!("failed to parse response from server: {e}"), span)
+    })
+}
+
+/// Sends `request` and returns the response body, or the error Alertmanager
+/// returned
+async fn response(request: RequestBuilder, span: Span) -> Result<Vec<u8>, LabeledError> {
+    let response = request.send().await.map_err(|e| client_error(e, span))?;
+
+    let status = response.status();
+
+    let body = response.bytes().await.map_err(|e| client_error(e, span))?;
+
+    if status.is_success() {
+        return Ok(body.to_vec());
+    }
+
+    Err(LabeledError::new("Alertmanager error")
+        .with_label(format!("{status}: {}", error_message(&body)), span))
+}
+
+/// Alertmanager errors are a JSON string, or plain text from a proxy
+fn error_message(body: &[u8]) -> String {
+    serde_json::from_slice(body)
+        .unwrap_or_else(|_| String::from_utf8_lossy(body).trim().to_string())
+}
+
+fn client_error(error: reqwest::Error, span: Span) -> LabeledError {
+    LabeledError::new("Alertmanager client error").with_label(error.to_string(), span)
+}
+
+fn alert_to_value(alert: Alert, flatten: bool, span: Span) -> Value {
+    let Alert {
+        labels,
+        annotations,
+        starts_at,
+        ends_at,
+        fingerprint,
+        receivers,
+        status,
+    } = alert;
+
+    let mut record = alert_record(
+        labels,
+        annotations,
+        status.state,
+        flatten,
+        &[
+            "starts_at",
+            "ends_at",
+            "receivers",
+            "silenced_by",
+            "inhibited_by",
+            "fingerprint",
+        ],
+        span,
+    );
+
+    let receivers = receivers
+        .into_iter()
+        .map(|receiver| Value::string(receiver.name, span))
+        .collect();
+
+    record.push("starts_at", rfc3339_to_value(&starts_at, span));
+    record.push("ends_at", rfc3339_to_value(&ends_at, span));
+    record.push("receivers", Value::list(receivers, span));
+    record.push("silenced_by", strings_to_value(status.silenced_by, span));
+    record.push("inhibited_by", strings_to_value(status.inhibited_by, span));
+    record.push("fingerprint", Value::string(fingerprint, span));
+
+    Value::record(record, span)
+}
+
+fn silence_to_value(silence: GettableSilence, span: Span) -> Value {
+    let matchers = silence
+        .matchers
+        .into_iter()
+        .map(|matcher| Matcher::from(matcher).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let record = record! {
+        "id" => Value::string(silence.id, span),
+        "state" => Value::string(silence.status.state, span),
+        "matchers" => Value::string(format!("{{{matchers}}}"), span),
+        "starts_at" => rfc3339_to_value(&silence.starts_at, span),
+        "ends_at" => rfc3339_to_value(&silence.ends_at, span),
+        "created_by" => Value::string(silence.created_by, span),
+        "comment" => Value::string(silence.comment, span),
+    };
+
+    Value::record(record, span)
+}
+
+fn strings_to_value(strings: Vec<String>, span: Span) -> Value {
+    Value::list(
+        strings
+            .into_iter()
+            .map(|string| Value::string(string, span))
+            .collect(),
+        span,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Alert, Alertmanager, GettableSilence, PostableSilence, SilenceMatcher};
+    use nu_protocol::{Span, Value, record};
+    use rstest::rstest;
+
+    fn alert() -> Alert {
+        serde_json::from_str(
+            r#"{
+              "annotations": {"summary": "Instance down"},
+              "endsAt": "2024-05-19T06:20:32.5Z",
+              "fingerprint": "6b8cb2d6e1a5b8c4",
+              "receivers": [{"name": "pager"}],
+              "startsAt": "2024-05-19T05:20:32.5Z",
+              "status": {"inhibitedBy": [], "silencedBy": ["a3b1"], "state": "suppressed"},
+              "updatedAt": "2024-05-19T05:25:32.5Z",
+              "generatorURL": "http://prometheus.example/graph",
+              "labels": {"alertname": "InstanceDown", "job": "node"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn date(time: &str) -> Value {
+        Value::test_date(chrono::DateTime::parse_from_rfc3339(time).unwrap())
+    }
+
+    #[rstest]
+    #[case(true, record! { "job" => Value::test_string("node") })]
+    #[case(false, record! {
+        "labels" => Value::test_record(record! { "job" => Value::test_string("node") }),
+    })]
+    fn alert_to_value(#[case] flatten: bool, #[case] labels: nu_protocol::Record) {
+        let value = super::alert_to_value(alert(), flatten, Span::test_data());
+
+        let mut expected = record! {
+            "alertname" => Value::test_string("InstanceDown"),
+            "state" => Value::test_string("suppressed"),
+        };
+
+        expected.extend(labels);
+
+        expected.extend(record! {
+            "annotations" => Value::test_record(record! {
+                "summary" => Value::test_string("Instance down"),
+            }),
+            "starts_at" => date("2024-05-19T05:20:32.5Z"),
+            "ends_at" => date("2024-05-19T06:20:32.5Z"),
+            "receivers" => Value::test_list(vec![Value::test_string("pager")]),
+            "silenced_by" => Value::test_list(vec![Value::test_string("a3b1")]),
+            "inhibited_by" => Value::test_list(vec![]),
+            "fingerprint" => Value::test_string("6b8cb2d6e1a5b8c4"),
+        });
+
+        assert_eq!(Value::test_record(expected), value);
+    }
+
+    #[rstest]
+    #[case(
+        "0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3c",
+        "0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3c"
+    )]
+    #[case("../status?x=1#y", "..%2Fstatus%3Fx=1%23y")]
+    #[case("a/b%2F", "a%2Fb%252F")]
+    fn silence_url(#[case] id: &str, #[case] expected: &str) {
+        let alertmanager = Alertmanager {
+            client: reqwest::Client::new(),
+            url: "https://alertmanager.example/prefix/".parse().unwrap(),
+        };
+
+        assert_eq!(
+            format!("https://alertmanager.example/prefix/api/v2/silence/{expected}"),
+            alertmanager.silence_url(id).as_str()
+        );
+    }
+
+    #[rstest]
+    #[case("0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3c", true)]
+    #[case("0F5C6D3E-8E6B-4D4A-9A41-6C1F0A1D2B3C", true)]
+    #[case("0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3", false)]
+    #[case("0f5c6d3e/8e6b-4d4a-9a41-6c1f0a1d2b3c", false)]
+    #[case("..", false)]
+    fn is_silence_id(#[case] id: &str, #[case] expected: bool) {
+        assert_eq!(expected, super::is_silence_id(id));
+    }
+
+    #[test]
+    fn alert_to_value_label_collision() {
+        let mut alert = alert();
+        alert.labels.insert("fingerprint".into(), "label".into());
+
+        let value = super::alert_to_value(alert, true, Span::test_data());
+        let record = value.as_record().unwrap();
+
+        let labels = record.get("labels").unwrap().as_record().unwrap();
+
+        assert_eq!(
+            Some(&Value::test_string("6b8cb2d6e1a5b8c4")),
+            record.get("fingerprint")
+        );
+        assert_eq!(Some(&Value::test_string("node")), labels.get("job"));
+        assert_eq!(
+            Some(&Value::test_string("label")),
+            labels.get("fingerprint")
+        );
+    }
+
+    #[rstest]
+    #[case(r#""silence not found""#, "silence not found")]
+    #[case("bad gateway\n", "bad gateway")]
+    fn error_message(#[case] body: &str, #[case] expected: &str) {
+        assert_eq!(expected, super::error_message(body.as_bytes()));
+    }
+
+    #[test]
+    fn postable_silence() {
+        let silence = PostableSilence {
+            matchers: vec![SilenceMatcher {
+                name: "job".into(),
+                value: "node".into(),
+                is_regex: false,
+                is_equal: true,
+            }],
+            starts_at: "2024-05-19T05:20:32.5Z".into(),
+            ends_at: "2024-05-19T07:20:32.5Z".into(),
+            created_by: "oncall".into(),
+            comment: "maintenance".into(),
+        };
+
+        let expected = serde_json::json!({
+            "matchers": [{"name": "job", "value": "node", "isRegex": false, "isEqual": true}],
+            "startsAt": "2024-05-19T05:20:32.5Z",
+            "endsAt": "2024-05-19T07:20:32.5Z",
+            "createdBy": "oncall",
+            "comment": "maintenance",
+        });
+
+        assert_eq!(expected, serde_json::to_value(silence).unwrap());
+    }
+
+    #[test]
+    fn silence_to_value() {
+        let silence: GettableSilence = serde_json::from_str(
+            r#"{
+              "id": "a3b1",
+              "status": {"state": "active"},
+              "updatedAt": "2024-05-19T05:20:32.5Z",
+              "matchers": [
+                {"name": "alertname", "value": "InstanceDown", "isRegex": false, "isEqual": true},
+                {"name": "job", "value": "node.*", "isRegex": true}
+              ],
+              "startsAt": "2024-05-19T05:20:32.5Z",
+              "endsAt": "2024-05-19T07:20:32.5Z",
+              "createdBy": "oncall",
+              "comment": "maintenance"
+            }"#,
+        )
+        .unwrap();
+
+        let value = super::silence_to_value(silence, Span::test_data());
+
+        let expected = Value::test_record(record! {
+            "id" => Value::test_string("a3b1"),
+            "state" => Value::test_string("active"),
+            "matchers" => Value::test_string(r#"{alertname="InstanceDown", job=~"node.*"}"#),
+            "starts_at" => date("2024-05-19T05:20:32.5Z"),
+            "ends_at" => date("2024-05-19T07:20:32.5Z"),
+            "created_by" => Value::test_string("oncall"),
+            "comment" => Value::test_string("maintenance"),
+        });
+
+        assert_eq!(expected, value);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{
+    Client,
+    client::{ApiRequest, response::deserialize_f64},
+    query::add_labels,
+    signals::run_with_signal,
+    time::rfc3339_to_value,
+};
+use nu_protocol::{
+    IntoInterruptiblePipelineData, LabeledError, PipelineData, Record, Signals, Span, Value, record,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct Alerts {
+    request: ApiRequest,
+    flatten: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertDiscovery {
+    alerts: Vec<Alert>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Alert {
+    labels: HashMap<String, String>,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+    state: String,
+    active_at: Option<String>,
+    #[serde(deserialize_with = "deserialize_f64")]
+    value: f64,
+}
+
+impl Alerts {
+    pub fn new(request: ApiRequest, flatten: bool) -> Self {
+        Self { request, flatten }
+    }
+
+    pub fn run(self, signals: &Signals, span: Span) -> Result<PipelineData, LabeledError> {
+        let Self {
+            ref request,
+            flatten,
+        } = self;
+
+        self.runtime()?.block_on(async {
+            let discovery: AlertDiscovery =
+                run_with_signal(signals, span, request.clone().get(span)).await??;
+
+            let alerts = discovery
+                .alerts
+                .into_iter()
+                .map(move |alert| alert_to_value(alert, flatten, span))
+                .into_pipeline_data(span, signals.clone());
+
+            Ok(alerts)
+        })
+    }
+}
+
+impl Client for Alerts {}
+
+/// Converts the alertname, state, labels, and annotations of an alert to a
+/// record.
+///
+/// When flattening, labels are nested in a `labels` record instead if one has
+/// the name of a column of the alert, including the `columns` the caller adds
+/// after the annotations.
+pub fn alert_record(
+    mut labels: HashMap<String, String>,
+    annotations: HashMap<String, String>,
+    state: String,
+    flatten: bool,
+    columns: &[&str],
+    span: Span,
+) -> Record {
+    let alertname = labels
+        .remove("alertname")
+        .unwrap_or("[UNKNOWN]".to_string());
+
+    let mut record = record! {
+        "alertname" => Value::string(alertname, span),
+        "state" => Value::string(state, span),
+    };
+
+    let collides = labels.keys().any(|name| {
+        ["state", "annotations"].contains(&name.as_str()) || columns.contains(&name.as_str())
+    });
+
+    add_labels(&mut record, &labels, flatten && !collides, span);
+
+    let mut names: Vec<_> = annotations.keys().collect();
+    names.sort();
+
+    let mut annotations_record = Record::new();
+
+    for name in names {
+        annotations_record.push(name, Value::string(&annotations[name], span));
+    }
+
+    record.push("annotations", Value::record(annotations_record, span));
+
+    record
+}
+
+fn alert_to_value(alert: Alert, flatten: bool, span: Span) -> Value {
+    let Alert {
+        labels,
+        annotations,
+        state,
+        active_at,
+        value,
+    } = alert;
+
+    let mut record = alert_record(
+        labels,
+        annotations,
+        state,
+        flatten,
+        &["active_at", "value"],
+        span,
+    );
+
+    let active_at = active_at.map_or(Value::nothing(span), |active_at| {
+        rfc3339_to_value(&active_at, span)
+    });
+
+    record.push("active_at", active_at);
+    record.push("value", Value::float(value, span));
+
+    Value::record(record, span)
+}
+
+#[cfg(test)]
+mod test {
+    use super::AlertDiscovery;
+    use nu_protocol::{Span, Value, record};
+    use rstest::rstest;
+
+    fn discovery() -> AlertDiscovery {
+        serde_json::from_str(
+            r#"{
+              "alerts": [
+                {
+                  "activeAt": "2024-05-19T05:20:32.5Z",
+                  "annotations": {"summary": "High request latency"},
+                  "labels": {"alertname": "HighRequestLatency", "severity": "page"},
+                  "state": "firing",
+                  "value": "1e+00"
+                }
+              ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(true, record! { "severity" => Value::test_string("page") })]
+    #[case(false, record! {
+        "labels" => Value::test_record(record! { "severity" => Value::test_string("page") }),
+    })]
+    fn alert_to_value(#[case] flatten: bool, #[case] labels: nu_protocol::Record) {
+        let alert = discovery().alerts.into_iter().next().unwrap();
+
+        let value = super::alert_to_value(alert, flatten, Span::test_data());
+
+        let mut expected = record! {
+            "alertname" => Value::test_string("HighRequestLatency"),
+            "state" => Value::test_string("firing"),
+        };
+
+        expected.extend(labels);
+
+        expected.push(
+            "annotations",
+            Value::test_record(record! {
+                "summary" => Value::test_string("High request latency"),
+            }),
+        );
+        expected.push(
+            "active_at",
+            Value::test_date(
+                chrono::DateTime::parse_from_rfc3339("2024-05-19T05:20:32.5Z").unwrap(),
+            ),
+        );
+        expected.push("value", Value::test_float(1.0));
+
+        assert_eq!(Value::test_record(expected), value);
+    }
+
+    #[rstest]
+    #[case("state")]
+    #[case("annotations")]
+    #[case("value")]
+    fn alert_to_value_label_collision(#[case] name: &str) {
+        let mut alert = discovery().alerts.into_iter().next().unwrap();
+        alert.labels.insert(name.into(), "label".into());
+
+        let value = super::alert_to_value(alert, true, Span::test_data());
+        let record = value.as_record().unwrap();
+
+        let labels = record.get("labels").unwrap().as_record().unwrap();
+
+        assert_eq!(Some(&Value::test_string("firing")), record.get("state"));
+        assert_eq!(Some(&Value::test_float(1.0)), record.get("value"));
+        assert_eq!(Some(&Value::test_string("page")), labels.get("severity"));
+        assert_eq!(Some(&Value::test_string("label")), labels.get(name));
+    }
+}
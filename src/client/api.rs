@@ -0,0 +1,266 @@
+use crate::client::{api_url, labeled_error};
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use nu_protocol::{LabeledError, Span};
+use prometheus_http_query::{Client, error::PrometheusError};
+use reqwest::StatusCode;
+use serde::{Deserialize, de::DeserializeOwned};
+
+/// A request to the Prometheus HTTP API.
+///
+/// The prometheus_http_query builders only accept whole second timestamps so
+/// requests that need more are sent directly with the inner HTTP client.
+#[derive(Clone)]
+pub struct ApiRequest {
+    client: Client,
+    path: String,
+    params: Vec<(&'static str, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum ApiResponse<T> {
+    Success {
+        data: T,
+        #[serde(default)]
+        warnings: Vec<String>,
+        #[serde(default)]
+        infos: Vec<String>,
+    },
+    Error(PrometheusError),
+}
+
+/// Response data with the warnings and infos Prometheus returned with it
+pub struct Annotated<T> {
+    pub data: T,
+    pub warnings: Vec<String>,
+    pub infos: Vec<String>,
+}
+
+impl ApiRequest {
+    pub fn new(client: Client, path: impl Into<String>) -> Self {
+        Self {
+            client,
+            path: path.into(),
+            params: vec![],
+        }
+    }
+
+    pub fn param(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Adds a timestamp parameter keeping sub-second precision
+    pub fn time(self, name: &'static str, time: DateTime<FixedOffset>) -> Self {
+        self.param(name, timestamp(time))
+    }
+
+    /// Sends the request and returns the response data.
+    ///
+    /// Errors are labeled with `span`.
+    pub async fn get<T: DeserializeOwned>(self, span: Span) -> Result<T, LabeledError> {
+        Ok(self.get_annotated(span).await?.data)
+    }
+
+    /// Sends the request and returns the response data with any warnings and
+    /// infos
+    pub async fn get_annotated<T: DeserializeOwned>(
+        self,
+        span: Span,
+    ) -> Result<Annotated<T>, LabeledError> {
+        let response = self
+            .client
+            .inner()
+            .get(self.url())
+            .send()
+            .await
+            .map_err(|e| client_error(e, span))?;
+
+        let status = response.status();
+
+        let body = response.bytes().await.map_err(|e| client_error(e, span))?;
+
+        annotated(status, &body, span)
+    }
+
+    fn url(&self) -> reqwest::Url {
+        let mut url = api_url(&self.client, &self.path);
+
+        url.query_pairs_mut()
+            .extend_pairs(self.params.iter().map(|(name, value)| (name, value)));
+
+        url
+    }
+}
+
+/// Parses a response body.
+///
+/// Prometheus reports errors as JSON with an error status, but a proxy or
+/// gateway in front of it may not, so any other failure status is reported
+/// with the body text.
+fn annotated<T: DeserializeOwned>(
+    status: StatusCode,
+    body: &[u8],
+    span: Span,
+) -> Result<Annotated<T>, LabeledError> {
+    let response = serde_json::from_slice(body);
+
+    if !status.is_success() && !matches!(response, Ok(ApiResponse::Error(_))) {
+        let body = String::from_utf8_lossy(body);
+
+        return Err(LabeledError::new("Prometheus server error")
+            .with_label(format!("{status}: {}", body.trim()), span));
+    }
+
+    match response {
+        Ok(ApiResponse::Success {
+            data,
+            warnings,
+            infos,
+        }) => Ok(Annotated {
+            data,
+            warnings,
+            infos,
+        }),
+        Ok(ApiResponse::Error(e)) => Err(labeled_error(
+            prometheus_http_query::Error::Prometheus(e),
+            span,
+        )),
+        Err(e) => Err(LabeledError::new("Prometheus client error")
+            .with_label(format!("failed to parse response from server: {e}"), span)),
+    }
+}
+
+fn client_error(error: reqwest::Error, span: Span) -> LabeledError {
+    LabeledError::new("Prometheus client error").with_label(error.to_string(), span)
+}
+
+/// Formats `time` as RFC 3339 which Prometheus accepts without losing
+/// sub-second precision
+pub fn timestamp(time: DateTime<FixedOffset>) -> String {
+    time.to_utc().to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Annotated, ApiRequest, ApiResponse};
+    use chrono::DateTime;
+    use nu_protocol::Span;
+    use prometheus_http_query::{Client, error::PrometheusErrorType};
+    use reqwest::StatusCode;
+    use rstest::rstest;
+
+    #[test]
+    fn api_response_success() {
+        let response: ApiResponse<Vec<String>> =
+            serde_json::from_str(r#"{"status":"success","data":["__name__","job"]}"#).unwrap();
+
+        let ApiResponse::Success {
+            data,
+            warnings,
+            infos,
+        } = response
+        else {
+            unreachable!("expected success");
+        };
+
+        assert_eq!(vec!["__name__".to_string(), "job".to_string()], data);
+        assert!(warnings.is_empty());
+        assert!(infos.is_empty());
+    }
+
+    #[test]
+    fn api_response_success_annotated() {
+        let response: ApiResponse<Vec<String>> = serde_json::from_str(
+            r#"{"status":"success","data":[],"warnings":["partial response"],"infos":["metric might not be a counter"]}"#,
+        )
+        .unwrap();
+
+        let ApiResponse::Success {
+            warnings, infos, ..
+        } = response
+        else {
+            unreachable!("expected success");
+        };
+
+        assert_eq!(vec!["partial response".to_string()], warnings);
+        assert_eq!(vec!["metric might not be a counter".to_string()], infos);
+    }
+
+    #[test]
+    fn api_response_error() {
+        let response: ApiResponse<Vec<String>> = serde_json::from_str(
+            r#"{"status":"error","errorType":"bad_data","error":"parse error"}"#,
+        )
+        .unwrap();
+
+        let ApiResponse::Error(error) = response else {
+            unreachable!("expected error");
+        };
+
+        assert_eq!(PrometheusErrorType::BadData, error.error_type());
+        assert_eq!("parse error", error.message());
+    }
+
+    #[rstest]
+    #[case(
+        StatusCode::UNAUTHORIZED,
+        "Unauthorized\n",
+        "Prometheus server error",
+        "401 Unauthorized: Unauthorized"
+    )]
+    #[case(
+        StatusCode::BAD_GATEWAY,
+        "<html>Bad Gateway</html>",
+        "Prometheus server error",
+        "502 Bad Gateway: <html>Bad Gateway</html>"
+    )]
+    #[case(
+        StatusCode::BAD_REQUEST,
+        r#"{"status":"error","errorType":"bad_data","error":"parse error"}"#,
+        "Prometheus error",
+        "parse error"
+    )]
+    fn annotated_error(
+        #[case] status: StatusCode,
+        #[case] body: &str,
+        #[case] message: &str,
+        #[case] label: &str,
+    ) {
+        let result: Result<Annotated<Vec<String>>, _> =
+            super::annotated(status, body.as_bytes(), Span::test_data());
+
+        let Err(error) = result else {
+            unreachable!("expected error");
+        };
+
+        assert_eq!(message, error.msg);
+        assert!(
+            error.labels[0].text.contains(label),
+            "{}",
+            error.labels[0].text
+        );
+    }
+
+    #[test]
+    fn url() {
+        let client = Client::try_from("https://prometheus.example/prometheus").unwrap();
+        let at = DateTime::parse_from_rfc3339("2024-05-19T05:20:32.5Z").unwrap();
+
+        let request = ApiRequest::new(client, "api/v1/query")
+            .param("query", "up{job=\"node\"}")
+            .time("time", at);
+
+        assert_eq!(
+            "https://prometheus.example/prometheus/api/v1/query?query=up%7Bjob%3D%22node%22%7D&time=2024-05-19T05%3A20%3A32.500Z",
+            request.url().as_str()
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        let time = DateTime::parse_from_rfc3339("2024-05-19T05:20:32.123+02:00").unwrap();
+
+        assert_eq!("2024-05-19T03:20:32.123Z", super::timestamp(time));
+    }
+}
@@ -1,17 +1,16 @@
-use crate::{Client, client::labeled_error, signals::run_with_signal};
+use crate::{Client, client::ApiRequest, signals::run_with_signal};
 use nu_protocol::{
     IntoInterruptiblePipelineData, LabeledError, PipelineData, Signals, Span, Value,
 };
-use prometheus_http_query::LabelNamesQueryBuilder;
 
 pub struct LabelNames {
-    query: LabelNamesQueryBuilder,
+    query: ApiRequest,
     selectors_span: Span,
     call_span: Span,
 }
 
 impl LabelNames {
-    pub fn new(query: LabelNamesQueryBuilder, selectors_span: Span, call_span: Span) -> Self {
+    pub fn new(query: ApiRequest, selectors_span: Span, call_span: Span) -> Self {
         Self {
             query,
             selectors_span,
@@ -29,9 +28,8 @@ impl LabelNames {
         } = self;
 
         runtime.block_on(async {
-            let response = run_with_signal(signals, call_span, query.clone().get())
-                .await?
-                .map_err(|error| labeled_error(error, query_span))?;
+            let response: Vec<String> =
+                run_with_signal(signals, call_span, query.clone().get(query_span)).await??;
 
             let names = response
                 .into_iter()
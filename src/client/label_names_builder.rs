@@ -1,7 +1,7 @@
-use crate::client::SelectorParser;
+use crate::client::{ApiRequest, SelectorParser};
 use chrono::{DateTime, FixedOffset};
 use nu_protocol::{LabeledError, Value};
-use prometheus_http_query::{Client, LabelNamesQueryBuilder};
+use prometheus_http_query::Client;
 
 pub struct LabelNamesBuilder {
     client: Client,
@@ -17,22 +17,20 @@ impl LabelNamesBuilder {
         start: Option<DateTime<FixedOffset>>,
         end: Option<DateTime<FixedOffset>>,
         selectors: &Value,
-    ) -> Result<LabelNamesQueryBuilder, LabeledError> {
+    ) -> Result<ApiRequest, LabeledError> {
         let span = selectors.span();
 
-        let builder = self.client.label_names();
+        let mut builder = ApiRequest::new(self.client, "api/v1/labels");
 
-        let mut builder = match selectors {
-            Value::Nothing { .. } => builder,
-            Value::String { .. } => builder.selectors(vec![SelectorParser::parse(selectors)?]),
+        match selectors {
+            Value::Nothing { .. } => (),
+            Value::String { .. } => {
+                builder = builder.param("match[]", SelectorParser::parse(selectors)?);
+            }
             Value::List { vals: values, .. } => {
-                let mut selectors = vec![];
-
                 for selector in values {
-                    selectors.push(SelectorParser::parse(selector)?);
+                    builder = builder.param("match[]", SelectorParser::parse(selector)?);
                 }
-
-                builder.selectors(selectors)
             }
             _ => {
                 return Err(LabeledError::new("Invalid input type")
@@ -41,11 +39,11 @@ impl LabelNamesBuilder {
         };
 
         if let Some(start) = start {
-            builder = builder.start(start.timestamp());
+            builder = builder.time("start", start);
         }
 
         if let Some(end) = end {
-            builder = builder.end(end.timestamp());
+            builder = builder.time("end", end);
         }
 
         Ok(builder)
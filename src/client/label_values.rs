@@ -1,17 +1,16 @@
-use crate::{Client, client::labeled_error, signals::run_with_signal};
+use crate::{Client, client::ApiRequest, signals::run_with_signal};
 use nu_protocol::{
     IntoInterruptiblePipelineData, LabeledError, PipelineData, Signals, Span, Value,
 };
-use prometheus_http_query::LabelValuesQueryBuilder;
 
 pub struct LabelValues {
-    query: LabelValuesQueryBuilder,
+    query: ApiRequest,
     labels_span: Span,
     call_span: Span,
 }
 
 impl LabelValues {
-    pub fn new(query: LabelValuesQueryBuilder, labels_span: Span, call_span: Span) -> Self {
+    pub fn new(query: ApiRequest, labels_span: Span, call_span: Span) -> Self {
         Self {
             query,
             labels_span,
@@ -29,9 +28,8 @@ impl LabelValues {
         } = self;
 
         runtime.block_on(async {
-            let response = run_with_signal(signals, call_span, query.clone().get())
-                .await?
-                .map_err(|error| labeled_error(error, labels_span))?;
+            let response: Vec<String> =
+                run_with_signal(signals, call_span, query.clone().get(labels_span)).await??;
 
             let names = response
                 .into_iter()
@@ -1,7 +1,7 @@
-use crate::client::SelectorParser;
+use crate::client::{ApiRequest, SelectorParser};
 use chrono::{DateTime, FixedOffset};
 use nu_protocol::{LabeledError, Value};
-use prometheus_http_query::{Client, LabelValuesQueryBuilder};
+use prometheus_http_query::Client;
 
 pub struct LabelValuesBuilder {
     client: Client,
@@ -18,23 +18,21 @@ impl LabelValuesBuilder {
         start: Option<DateTime<FixedOffset>>,
         end: Option<DateTime<FixedOffset>>,
         selectors: &Vec<Value>,
-    ) -> Result<LabelValuesQueryBuilder, LabeledError> {
-        let label = label.as_str()?.to_string();
+    ) -> Result<ApiRequest, LabeledError> {
+        let label = label.as_str()?;
 
-        let mut builder = self.client.label_values(label);
+        let mut builder = ApiRequest::new(self.client, format!("api/v1/label/{label}/values"));
 
         for selector in selectors {
-            let selector = SelectorParser::parse(selector)?;
-
-            builder = builder.selectors(vec![selector]);
+            builder = builder.param("match[]", SelectorParser::parse(selector)?);
         }
 
         if let Some(start) = start {
-            builder = builder.start(start.timestamp());
+            builder = builder.time("start", start);
         }
 
         if let Some(end) = end {
-            builder = builder.end(end.timestamp());
+            builder = builder.time("end", end);
         }
 
         Ok(builder)
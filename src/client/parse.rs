@@ -1,11 +1,17 @@
-use crate::Client;
+use crate::{
+    client::sample_parser::{self, Exemplar, Line, LineError, SampleExtras},
+    exposition::SAMPLE_COLUMNS,
+    Client,
+};
+use nom::Offset;
+use nom_language::error::{VerboseError, VerboseErrorKind};
 use nom_openmetrics::{
-    parser::{openmetrics, prometheus},
+    parser::{family, openmetrics, prometheus},
     Family, MetricDescriptor, Sample,
 };
 use nu_protocol::{record, LabeledError, Record, Span, Value};
 
-#[derive(Default)]
+#[derive(Clone, Copy, Default)]
 pub enum ParseFormat {
     #[default]
     Prometheus,
@@ -15,6 +21,8 @@ pub enum ParseFormat {
 pub struct Parse<'a> {
     input: &'a Value,
     format: ParseFormat,
+    flatten: bool,
+    lenient: bool,
 }
 
 impl<'a> Parse<'a> {
@@ -22,33 +30,313 @@ impl<'a> Parse<'a> {
         Self {
             input,
             format: Default::default(),
+            flatten: true,
+            lenient: false,
         }
     }
 
     pub fn run(self) -> Result<Value, LabeledError> {
-        let Self { input, format } = self;
+        let Self {
+            input,
+            format,
+            flatten,
+            lenient,
+        } = self;
 
-        let (_, families) = match format {
-            ParseFormat::Prometheus => prometheus(input.as_str()?).unwrap(),
-            ParseFormat::Openmetrics => openmetrics(input.as_str()?).unwrap(),
-        };
+        let span = input.span();
+        let text = input.as_str()?;
 
-        let families = families
-            .iter()
-            .map(|family| family_to_value(family))
-            .collect();
+        let mut lines = vec![];
+        let mut errors = vec![];
+
+        for line in sample_parser::lines(text, format) {
+            match line {
+                Ok(line) => lines.push(line),
+                Err(error) if lenient => errors.push(error),
+                Err(error) => return Err(error.labeled_error(span)),
+            }
+        }
+
+        if !lenient {
+            let (exposition, starts) = join(&lines);
+
+            let parsed = match format {
+                ParseFormat::Prometheus => prometheus(&exposition),
+                ParseFormat::Openmetrics => openmetrics(&exposition),
+            };
+
+            return match parsed {
+                Ok((_, families)) => {
+                    let families = families_to_values(&families, &mut lines, flatten, span)?;
+
+                    Ok(Value::list(families, Span::unknown()))
+                }
+                Err(error) => {
+                    let (_, error) =
+                        locate_error(&exposition, &starts, &lines, text, error, format);
+
+                    Err(error.labeled_error(span))
+                }
+            };
+        }
+
+        if matches!(format, ParseFormat::Openmetrics) {
+            match lines.last() {
+                Some(line) if line.text == "# EOF" => {
+                    lines.pop();
+                }
+                _ => {
+                    let end_line = text.split_inclusive('\n').count() + 1;
+                    let error = LineError::new(end_line, text.len(), "", 0, "expected # EOF");
+
+                    errors.push(error);
+                }
+            }
+        }
+
+        let mut families = vec![];
+
+        for chunk in family_chunks(lines) {
+            families.extend(lenient_family(chunk, text, flatten, span, &mut errors)?);
+        }
+
+        let families = Value::list(families, Span::unknown());
+
+        errors.sort_by_key(|error| (error.line, error.column));
+
+        let errors = errors.iter().map(error_to_value).collect();
+
+        let record = record! {
+            "families" => families,
+            "errors" => Value::list(errors, Span::unknown()),
+        };
 
-        Ok(Value::list(families, Span::unknown()))
+        Ok(Value::record(record, Span::unknown()))
     }
 
     pub fn set_format(&mut self, format: ParseFormat) {
         self.format = format;
     }
+
+    /// Place sample labels in a "labels" column when `flatten` is false
+    pub fn set_flatten(&mut self, flatten: bool) {
+        self.flatten = flatten;
+    }
+
+    /// Skip invalid lines, reporting them in an "errors" column, when
+    /// `lenient` is true
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
 }
 
 impl<'a> Client for Parse<'a> {}
 
-fn family_to_value(family: &Family) -> Value {
+/// Splits lines into the lines of each family.
+///
+/// A family is its descriptors followed by its samples, so a family starts at
+/// each descriptor line following a sample line.
+fn family_chunks(lines: Vec<Line<'_>>) -> Vec<Vec<Line<'_>>> {
+    let mut chunks: Vec<Vec<Line>> = vec![];
+
+    for line in lines {
+        let starts_family = line.text.starts_with('#')
+            && chunks
+                .last()
+                .and_then(|chunk| chunk.last())
+                .is_some_and(|last| !last.text.starts_with('#'));
+
+        match chunks.last_mut() {
+            Some(chunk) if !starts_family => chunk.push(line),
+            _ => chunks.push(vec![line]),
+        }
+    }
+
+    chunks
+}
+
+/// Parses the lines of one family, removing invalid lines into `errors`.
+///
+/// Only the lines of this family are parsed again after an invalid line, so
+/// invalid lines do not cause the whole exposition to be parsed again.
+fn lenient_family(
+    mut lines: Vec<Line>,
+    text: &str,
+    flatten: bool,
+    span: Span,
+    errors: &mut Vec<LineError>,
+) -> Result<Vec<Value>, LabeledError> {
+    while !lines.is_empty() {
+        let (exposition, starts) = join(&lines);
+
+        // Any # EOF was removed, so the family parses like a Prometheus
+        // exposition
+        let error = match prometheus(&exposition) {
+            Ok((_, families)) => return families_to_values(&families, &mut lines, flatten, span),
+            Err(error) => error,
+        };
+
+        let (index, error) = locate_error(
+            &exposition,
+            &starts,
+            &lines,
+            text,
+            error,
+            ParseFormat::Prometheus,
+        );
+
+        let Some(index) = index else {
+            return Err(error.labeled_error(span));
+        };
+
+        lines.remove(index);
+        errors.push(error);
+    }
+
+    Ok(vec![])
+}
+
+/// Converts parsed families to values, pairing each sample with the extras of
+/// its line
+fn families_to_values(
+    families: &[Family],
+    lines: &mut [Line],
+    flatten: bool,
+    span: Span,
+) -> Result<Vec<Value>, LabeledError> {
+    let mut extras = lines.iter_mut().filter_map(|line| line.extras.take());
+
+    let families = families
+        .iter()
+        .map(|family| family_to_value(family, &mut extras, flatten, span))
+        .collect::<Result<_, _>>()?;
+
+    if extras.next().is_some() {
+        return Err(extras_mismatch(span));
+    }
+
+    Ok(families)
+}
+
+/// Joins lines into the text the exposition parser reads, returning the offset
+/// of each line within it
+fn join(lines: &[Line]) -> (String, Vec<usize>) {
+    let mut exposition = String::new();
+    let mut starts = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        starts.push(exposition.len());
+        exposition.push_str(&line.text);
+
+        if line.newline {
+            exposition.push('\n');
+        }
+    }
+
+    (exposition, starts)
+}
+
+/// Locates an error from parsing the joined `exposition` in the input `text`.
+///
+/// Returns the index of the line with the error, or `None` when the error is
+/// at the end of an OpenMetrics exposition.
+fn locate_error(
+    exposition: &str,
+    starts: &[usize],
+    lines: &[Line],
+    text: &str,
+    error: nom::Err<VerboseError<&str>>,
+    format: ParseFormat,
+) -> (Option<usize>, LineError) {
+    let (offset, message) = describe(exposition, &error);
+
+    // Parsing stops before an invalid family, so parse it again for the cause
+    let (offset, message) = match family(&exposition[offset..]) {
+        Err(error) if offset < exposition.len() => match describe(exposition, &error) {
+            (end, _) if end == exposition.len() && exposition[offset..].starts_with('#') => {
+                (offset, "expected a sample".into())
+            }
+            cause => cause,
+        },
+        _ => (offset, message),
+    };
+
+    let end_line = text.split_inclusive('\n').count() + 1;
+
+    let index = starts
+        .partition_point(|start| *start <= offset)
+        .checked_sub(1)
+        .filter(|index| offset <= starts[*index] + lines[*index].text.len());
+
+    match (index, format) {
+        (_, ParseFormat::Openmetrics) if offset == exposition.len() => (
+            None,
+            LineError::new(end_line, text.len(), "", 0, "expected # EOF"),
+        ),
+        (Some(index), _) => {
+            let line = &lines[index];
+
+            let error = LineError::new(
+                line.number,
+                line.offset,
+                line.original,
+                line.original_offset(offset - starts[index]),
+                message,
+            );
+
+            (Some(index), error)
+        }
+        (None, _) => (None, LineError::new(end_line, text.len(), "", 0, message)),
+    }
+}
+
+/// The offset in `exposition` and a description of a parse error
+fn describe(exposition: &str, error: &nom::Err<VerboseError<&str>>) -> (usize, String) {
+    let (nom::Err::Error(error) | nom::Err::Failure(error)) = error else {
+        return (exposition.len(), "incomplete input".into());
+    };
+
+    let Some((substring, kind)) = error.errors.first() else {
+        return (exposition.len(), "invalid input".into());
+    };
+
+    let context = error.errors.iter().find_map(|(_, kind)| match kind {
+        VerboseErrorKind::Context(context) => Some(context),
+        _ => None,
+    });
+
+    let message = match (kind, context) {
+        (VerboseErrorKind::Context(context), _) => format!("invalid {context}"),
+        (VerboseErrorKind::Char(expected), Some(context)) => {
+            format!("expected {expected:?} in {context}")
+        }
+        (VerboseErrorKind::Char(expected), None) => format!("expected {expected:?}"),
+        (VerboseErrorKind::Nom(_), Some(context)) => format!("unexpected input in {context}"),
+        (VerboseErrorKind::Nom(_), None) => "unexpected input".into(),
+    };
+
+    (exposition.offset(substring), message)
+}
+
+fn error_to_value(error: &LineError) -> Value {
+    let record = record! {
+        "line" => Value::int(error.line as i64, Span::unknown()),
+        "column" => Value::int(error.column as i64, Span::unknown()),
+        "text" => Value::string(&error.text, Span::unknown()),
+        "error" => Value::string(&error.message, Span::unknown()),
+    };
+
+    Value::record(record, Span::unknown())
+}
+
+/// Converts a family to a value, taking the extras of each of its samples in
+/// order
+fn family_to_value(
+    family: &Family,
+    extras: &mut impl Iterator<Item = SampleExtras>,
+    flatten: bool,
+    span: Span,
+) -> Result<Value, LabeledError> {
     let descriptors = family
         .descriptors
         .iter()
@@ -58,15 +346,28 @@ fn family_to_value(family: &Family) -> Value {
     let samples = family
         .samples
         .iter()
-        .map(|sample| sample_to_value(sample))
-        .collect();
+        .map(|sample| {
+            let extras = extras.next().ok_or_else(|| extras_mismatch(span))?;
+
+            Ok(sample_to_value(sample, extras, flatten))
+        })
+        .collect::<Result<_, LabeledError>>()?;
 
     let record = record! {
         "descriptors" => Value::list(descriptors, Span::unknown()),
         "samples" => Value::list(samples, Span::unknown()),
     };
 
-    Value::record(record, Span::unknown())
+    Ok(Value::record(record, Span::unknown()))
+}
+
+/// The sample lines found did not match the samples parsed, so timestamps and
+/// exemplars cannot be paired with their samples
+fn extras_mismatch(span: Span) -> LabeledError {
+    LabeledError::new("Metrics parse error").with_label(
+        "sample lines do not match the parsed samples, timestamps and exemplars cannot be paired",
+        span,
+    )
 }
 
 fn descriptor_to_value(descriptor: &MetricDescriptor) -> Value {
@@ -91,11 +392,332 @@ fn descriptor_to_value(descriptor: &MetricDescriptor) -> Value {
     Value::record(record, Span::unknown())
 }
 
-fn sample_to_value(sample: &Sample) -> Value {
+fn sample_to_value(sample: &Sample, extras: SampleExtras, flatten: bool) -> Value {
     let mut record = Record::new();
 
     record.insert("name", Value::string(sample.name(), Span::unknown()));
+
+    let labels = sample
+        .labels()
+        .iter()
+        .map(|label| (label.name, label.value.as_str()));
+
+    // A label named like a sample column is nested so it is not lost and
+    // `to prometheus` reads it back as a label
+    let collides = sample
+        .labels()
+        .iter()
+        .any(|label| SAMPLE_COLUMNS.contains(&label.name));
+
+    if flatten && !collides {
+        for (name, value) in labels {
+            record.push(name, Value::string(value, Span::unknown()));
+        }
+    } else {
+        record.insert("labels", labels_to_value(labels));
+    }
+
     record.insert("value", Value::float(sample.number(), Span::unknown()));
 
+    if let Some(timestamp) = extras.timestamp {
+        record.insert("timestamp", Value::date(timestamp, Span::unknown()));
+    }
+
+    if let Some(exemplar) = extras.exemplar {
+        record.insert("exemplar", exemplar_to_value(exemplar));
+    }
+
+    Value::record(record, Span::unknown())
+}
+
+fn exemplar_to_value(exemplar: Exemplar) -> Value {
+    let labels = exemplar
+        .labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()));
+
+    let mut record = record! {
+        "labels" => labels_to_value(labels),
+        "value" => Value::float(exemplar.value, Span::unknown()),
+    };
+
+    if let Some(timestamp) = exemplar.timestamp {
+        record.insert("timestamp", Value::date(timestamp, Span::unknown()));
+    }
+
     Value::record(record, Span::unknown())
 }
+
+fn labels_to_value<'a>(labels: impl Iterator<Item = (&'a str, &'a str)>) -> Value {
+    let labels = labels
+        .map(|(name, value)| (name.to_string(), Value::string(value, Span::unknown())))
+        .collect();
+
+    Value::record(labels, Span::unknown())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Parse, ParseFormat, SampleExtras};
+    use chrono::DateTime;
+    use nu_protocol::{Span, Value, record};
+    use rstest::rstest;
+
+    /// The number of samples in each family of a lenient parse
+    fn sample_counts(parsed: &Value) -> Vec<usize> {
+        let families = parsed.get_data_by_key("families").unwrap();
+
+        families
+            .as_list()
+            .unwrap()
+            .iter()
+            .map(|family| {
+                let samples = family.get_data_by_key("samples").unwrap();
+
+                samples.as_list().unwrap().len()
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(true, record! {
+        "name" => Value::test_string("http_requests_total"),
+        "code" => Value::test_string("200"),
+        "value" => Value::test_float(3.0),
+    })]
+    #[case(false, record! {
+        "name" => Value::test_string("http_requests_total"),
+        "labels" => Value::test_record(record! { "code" => Value::test_string("200") }),
+        "value" => Value::test_float(3.0),
+    })]
+    fn run_labels(#[case] flatten: bool, #[case] expected: nu_protocol::Record) {
+        let input = Value::test_string("http_requests_total{code=\"200\"} 3\n");
+
+        let mut parser = Parse::new(&input);
+        parser.set_flatten(flatten);
+
+        let families = parser.run().unwrap();
+        let sample = families.as_list().unwrap()[0]
+            .get_data_by_key("samples")
+            .unwrap();
+        let sample = sample.as_list().unwrap()[0].as_record().unwrap();
+
+        assert_eq!(&expected, sample);
+    }
+
+    #[rstest]
+    #[case(ParseFormat::Prometheus, "up{name=\"x\"} 1\n", "name")]
+    #[case(ParseFormat::Prometheus, "up{value=\"x\"} 1\n", "value")]
+    #[case(ParseFormat::Prometheus, "up{labels=\"x\"} 1\n", "labels")]
+    #[case(ParseFormat::Prometheus, "up{type=\"x\"} 1\n", "type")]
+    #[case(
+        ParseFormat::Prometheus,
+        "up{timestamp=\"x\"} 1 1700000000000\n",
+        "timestamp"
+    )]
+    #[case(
+        ParseFormat::Openmetrics,
+        "up{exemplar=\"x\"} 1 # {} 2\n# EOF\n",
+        "exemplar"
+    )]
+    fn run_label_collision(#[case] format: ParseFormat, #[case] input: &str, #[case] label: &str) {
+        let input = Value::test_string(input);
+
+        let mut parser = Parse::new(&input);
+        parser.set_format(format);
+
+        let families = parser.run().unwrap();
+        let sample = families.as_list().unwrap()[0]
+            .get_data_by_key("samples")
+            .unwrap();
+        let sample = sample.as_list().unwrap()[0].as_record().unwrap();
+
+        let columns: Vec<_> = sample.columns().map(String::as_str).collect();
+        let mut expected = vec!["name", "labels", "value"];
+
+        if label == "timestamp" || label == "exemplar" {
+            expected.push(label);
+        }
+
+        assert_eq!(expected, columns);
+        assert_eq!(Some(&Value::test_string("up")), sample.get("name"));
+        assert_eq!(
+            Some(&Value::test_record(
+                record! { label => Value::test_string("x") }
+            )),
+            sample.get("labels")
+        );
+    }
+
+    #[test]
+    fn run_whitespace() {
+        let input = Value::test_string("up \t1\t1700000000000\n");
+
+        let families = Parse::new(&input).run().unwrap();
+        let sample = families.as_list().unwrap()[0]
+            .get_data_by_key("samples")
+            .unwrap();
+        let sample = &sample.as_list().unwrap()[0];
+
+        assert_eq!(
+            Some(Value::test_float(1.0)),
+            sample.get_data_by_key("value")
+        );
+        assert_eq!(
+            Some(Value::test_date(
+                DateTime::from_timestamp_millis(1_700_000_000_000)
+                    .unwrap()
+                    .fixed_offset()
+            )),
+            sample.get_data_by_key("timestamp")
+        );
+    }
+
+    #[test]
+    fn family_to_value_extras_mismatch() {
+        let (_, families) = nom_openmetrics::parser::prometheus("up 1\nup 2\n").unwrap();
+
+        let mut extras = vec![SampleExtras::default()].into_iter();
+
+        let error =
+            super::family_to_value(&families[0], &mut extras, true, Span::test_data()).unwrap_err();
+
+        assert_eq!("Metrics parse error", error.msg);
+    }
+
+    #[test]
+    fn run_extras() {
+        let input = Value::test_string(
+            "# TYPE a counter\na_total 1 1700000000 # {trace_id=\"abc\"} 0.5 1700000000.25\n# EOF\n",
+        );
+
+        let mut parser = Parse::new(&input);
+        parser.set_format(ParseFormat::Openmetrics);
+
+        let families = parser.run().unwrap();
+        let sample = families.as_list().unwrap()[0]
+            .get_data_by_key("samples")
+            .unwrap();
+        let sample = &sample.as_list().unwrap()[0];
+
+        let timestamp = |millis| {
+            Value::test_date(
+                DateTime::from_timestamp_millis(millis)
+                    .unwrap()
+                    .fixed_offset(),
+            )
+        };
+
+        assert_eq!(
+            Some(timestamp(1_700_000_000_000)),
+            sample.get_data_by_key("timestamp")
+        );
+
+        let expected = Value::test_record(record! {
+            "labels" => Value::test_record(record! { "trace_id" => Value::test_string("abc") }),
+            "value" => Value::test_float(0.5),
+            "timestamp" => timestamp(1_700_000_000_250),
+        });
+
+        assert_eq!(Some(expected), sample.get_data_by_key("exemplar"));
+    }
+
+    #[rstest]
+    #[case(
+        ParseFormat::Prometheus,
+        "up 1\n0up 1\n",
+        "line 2, column 1: unexpected input in metric name",
+        (15, 20)
+    )]
+    #[case(
+        ParseFormat::Prometheus,
+        "# TYPE up gauge\nup one\n",
+        "line 2, column 4: unexpected input in number",
+        (29, 32)
+    )]
+    #[case(
+        ParseFormat::Prometheus,
+        "up 1",
+        "line 1, column 5: expected '\\n' in sample",
+        (14, 14)
+    )]
+    #[case(
+        ParseFormat::Openmetrics,
+        "up 1\n",
+        "line 2, column 1: expected # EOF",
+        (15, 15)
+    )]
+    #[case(
+        ParseFormat::Openmetrics,
+        "up 1 x\n# EOF\n",
+        "line 1, column 6: invalid timestamp \"x\"",
+        (15, 16)
+    )]
+    fn run_error(
+        #[case] format: ParseFormat,
+        #[case] input: &str,
+        #[case] expected: &str,
+        #[case] (start, end): (usize, usize),
+    ) {
+        let input = Value::string(input, Span::new(10, 10 + input.len()));
+
+        let mut parser = Parse::new(&input);
+        parser.set_format(format);
+
+        let error = parser.run().unwrap_err();
+
+        assert_eq!("Metrics parse error", error.msg);
+        assert_eq!(expected, error.labels[0].text);
+        assert_eq!(Span::new(start, end), error.labels[0].span);
+    }
+
+    #[test]
+    fn run_lenient() {
+        let input = Value::test_string("up 1\n# comment\nup{job=\"a\"} 2 soon\ndown 0\n");
+
+        let mut parser = Parse::new(&input);
+        parser.set_format(ParseFormat::Openmetrics);
+        parser.set_lenient(true);
+
+        let parsed = parser.run().unwrap();
+
+        assert_eq!(vec![1, 1], sample_counts(&parsed));
+
+        let error = |line, column, text: &str, error: &str| {
+            Value::test_record(record! {
+                "line" => Value::test_int(line),
+                "column" => Value::test_int(column),
+                "text" => Value::test_string(text),
+                "error" => Value::test_string(error),
+            })
+        };
+
+        let expected = Value::test_list(vec![
+            error(2, 1, "# comment", "unexpected input in metric name"),
+            error(3, 15, "up{job=\"a\"} 2 soon", "invalid timestamp \"soon\""),
+            error(5, 1, "", "expected # EOF"),
+        ]);
+
+        assert_eq!(Some(expected), parsed.get_data_by_key("errors"));
+    }
+
+    #[test]
+    fn run_lenient_family() {
+        let input = Value::test_string(
+            "# TYPE a counter\na_total 1\na_total{x=\"y\" 2\na_total 3\n# TYPE b gauge\nb 4\n",
+        );
+
+        let mut parser = Parse::new(&input);
+        parser.set_lenient(true);
+
+        let parsed = parser.run().unwrap();
+
+        assert_eq!(vec![2, 1], sample_counts(&parsed));
+
+        let errors = parsed.get_data_by_key("errors").unwrap();
+        let errors = errors.as_list().unwrap();
+
+        assert_eq!(1, errors.len());
+        assert_eq!(Some(Value::test_int(3)), errors[0].get_data_by_key("line"));
+    }
+}
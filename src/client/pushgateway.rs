@@ -0,0 +1,182 @@
+use crate::{
+    Client,
+    client::join_url,
+    signals::run_with_signal,
+    source::{Service, Source},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use nu_protocol::{LabeledError, Signals, Span, Value};
+use reqwest::{RequestBuilder, Url, header::CONTENT_TYPE};
+
+/// Content type of the text exposition format
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4";
+
+/// A client for the Pushgateway of a source
+pub struct Pushgateway {
+    client: reqwest::Client,
+    url: Url,
+}
+
+/// The job and grouping labels identifying a group of pushed metrics
+#[derive(Debug)]
+pub struct Grouping {
+    pub job: String,
+    pub labels: Vec<(String, String)>,
+}
+
+impl Pushgateway {
+    pub fn new(source: &Source) -> Result<Self, LabeledError> {
+        let client = source
+            .service_client_builder(Service::Pushgateway)?
+            .build()
+            .map_err(|e| {
+                LabeledError::new("Unable to build pushgateway client").with_help(e.to_string())
+            })?;
+
+        let url = source.service_request_url(Service::Pushgateway)?;
+
+        let url = Url::parse(&url).map_err(|e| {
+            LabeledError::new("Invalid pushgateway URL")
+                .with_label(format!("{url:?}: {e}"), source.span)
+        })?;
+
+        Ok(Self { client, url })
+    }
+
+    /// Pushes `metrics` in the text exposition format to the `grouping`.
+    ///
+    /// With `replace` all metrics in the group are replaced, otherwise only
+    /// metrics with the same names as those pushed.
+    pub fn push(
+        self,
+        grouping: &Grouping,
+        metrics: String,
+        replace: bool,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<Value, LabeledError> {
+        let url = self.grouping_url(grouping);
+
+        let request = if replace {
+            self.client.put(url)
+        } else {
+            self.client.post(url)
+        };
+
+        let request = request.header(CONTENT_TYPE, TEXT_FORMAT).body(metrics);
+
+        self.runtime()?.block_on(async {
+            run_with_signal(signals, span, send(request, span)).await??;
+
+            Ok(Value::nothing(span))
+        })
+    }
+
+    /// Deletes all metrics in the `grouping`
+    pub fn delete(
+        self,
+        grouping: &Grouping,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<Value, LabeledError> {
+        let request = self.client.delete(self.grouping_url(grouping));
+
+        self.runtime()?.block_on(async {
+            run_with_signal(signals, span, send(request, span)).await??;
+
+            Ok(Value::nothing(span))
+        })
+    }
+
+    fn grouping_url(&self, grouping: &Grouping) -> Url {
+        let mut url = join_url(&self.url, "metrics");
+
+        if let Ok(mut segments) = url.path_segments_mut() {
+            let labels = std::iter::once(("job", grouping.job.as_str())).chain(
+                grouping
+                    .labels
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            );
+
+            for (name, value) in labels {
+                let (name, value) = grouping_segments(name, value);
+
+                segments.push(&name).push(&value);
+            }
+        }
+
+        url
+    }
+}
+
+impl Client for Pushgateway {}
+
+/// Path segments for a grouping label.
+///
+/// Values that cannot be a path segment are base64 encoded, as the
+/// Pushgateway requires.  An empty value is encoded as `=`.
+fn grouping_segments(name: &str, value: &str) -> (String, String) {
+    if value.is_empty() {
+        (format!("{name}@base64"), "=".into())
+    } else if value.contains('/') {
+        (format!("{name}@base64"), URL_SAFE.encode(value))
+    } else {
+        (name.into(), value.into())
+    }
+}
+
+async fn send(request: RequestBuilder, span: Span) -> Result<(), LabeledError> {
+    let response = request.send().await.map_err(|e| {
+        LabeledError::new("Pushgateway client error").with_label(e.to_string(), span)
+    })?;
+
+    let status = response.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let body = response.text().await.unwrap_or_default();
+
+    Err(LabeledError::new("Pushgateway error")
+        .with_label(format!("{status}: {}", body.trim()), span))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grouping, Pushgateway};
+    use reqwest::Url;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("batch", &[], "https://pushgateway.example/metrics/job/batch")]
+    #[case(
+        "batch",
+        &[("instance", "db1")],
+        "https://pushgateway.example/metrics/job/batch/instance/db1"
+    )]
+    #[case(
+        "batch",
+        &[("path", "/var/tmp")],
+        "https://pushgateway.example/metrics/job/batch/path@base64/L3Zhci90bXA="
+    )]
+    #[case("batch", &[("instance", "")], "https://pushgateway.example/metrics/job/batch/instance@base64/=")]
+    #[case("a b", &[], "https://pushgateway.example/metrics/job/a%20b")]
+    fn grouping_url(#[case] job: &str, #[case] labels: &[(&str, &str)], #[case] expected: &str) {
+        let pushgateway = Pushgateway {
+            client: reqwest::Client::new(),
+            url: Url::parse("https://pushgateway.example/").unwrap(),
+        };
+
+        let grouping = Grouping {
+            job: job.into(),
+            labels: labels
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        };
+
+        assert_eq!(expected, pushgateway.grouping_url(&grouping).as_str());
+    }
+}
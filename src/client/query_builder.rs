@@ -1,31 +1,71 @@
-use crate::client::{QueryInstant, QueryRange};
-use chrono::{DateTime, FixedOffset};
+use crate::{
+    client::{ApiRequest, QueryInstant, QueryRange, ResultType},
+    query::{QueryOptions, Timestamps},
+};
+use chrono::{DateTime, FixedOffset, TimeDelta};
 use nu_protocol::Span;
 use prometheus_http_query::Client;
 
+/// Prometheus rejects range queries with more points per series than this
+const MAX_POINTS: f64 = 11_000.0;
+
 pub struct QueryBuilder {
     client: Client,
-    flatten: bool,
     timeout: Option<i64>,
+    concurrency: usize,
+    stats: bool,
+    options: QueryOptions,
 }
 
 impl QueryBuilder {
     pub fn new(client: Client) -> Self {
         QueryBuilder {
             client,
-            flatten: false,
             timeout: None,
+            concurrency: 1,
+            stats: false,
+            options: QueryOptions::default(),
         }
     }
 
     pub fn flatten(&mut self) {
-        self.flatten = true;
+        self.options.flatten = true;
     }
 
     pub fn timeout(&mut self, timeout: i64) {
         self.timeout = Some(timeout);
     }
 
+    pub fn timestamps(&mut self, timestamps: Timestamps) {
+        self.options.timestamps = timestamps;
+    }
+
+    /// Requires the query to return `result_type`
+    pub fn result_type(&mut self, result_type: ResultType) {
+        self.options.result_type = Some(result_type);
+    }
+
+    /// Returns query statistics in a record with the result
+    pub fn stats(&mut self) {
+        self.stats = true;
+    }
+
+    /// Returns the result in a record with its warnings and infos instead of
+    /// putting them in the `prometheus_warnings` and `prometheus_infos`
+    /// pipeline metadata
+    pub fn with_warnings(&mut self) {
+        self.options.annotations.wrap = true;
+    }
+
+    pub fn fail_on_warning(&mut self) {
+        self.options.annotations.fail_on_warning = true;
+    }
+
+    /// Sets how many chunks of a split range query are requested at once
+    pub fn concurrency(&mut self, concurrency: usize) {
+        self.concurrency = concurrency.max(1);
+    }
+
     pub fn instant(
         self,
         at: Option<DateTime<FixedOffset>>,
@@ -33,17 +73,21 @@ impl QueryBuilder {
         query_span: Span,
         call_span: Span,
     ) -> QueryInstant {
-        let mut query = self.client.query(query);
+        let mut query = ApiRequest::new(self.client, "api/v1/query").param("query", query);
 
         if let Some(at) = at {
-            query = query.at(at.timestamp());
+            query = query.time("time", at);
         }
 
         if let Some(timeout) = self.timeout {
-            query = query.timeout(timeout);
+            query = query.param("timeout", format!("{timeout}ms"));
+        }
+
+        if self.stats {
+            query = query.param("stats", "all");
         }
 
-        QueryInstant::new(query, query_span, self.flatten, call_span)
+        QueryInstant::new(query, query_span, self.options, call_span)
     }
 
     pub fn range(
@@ -55,15 +99,101 @@ impl QueryBuilder {
         query_span: Span,
         call_span: Span,
     ) -> QueryRange {
-        let start = start.timestamp();
-        let end = end.timestamp();
+        let queries = chunks(start, end, step)
+            .into_iter()
+            .map(|(start, end)| {
+                let mut query = ApiRequest::new(self.client.clone(), "api/v1/query_range")
+                    .param("query", query)
+                    .time("start", start)
+                    .time("end", end)
+                    .param("step", step);
 
-        let mut query = self.client.query_range(query, start, end, step);
+                if let Some(timeout) = self.timeout {
+                    query = query.param("timeout", format!("{timeout}ms"));
+                }
 
-        if let Some(timeout) = self.timeout {
-            query = query.timeout(timeout);
-        }
+                if self.stats {
+                    query = query.param("stats", "all");
+                }
+
+                query
+            })
+            .collect();
+
+        QueryRange::new(
+            queries,
+            query_span,
+            self.options,
+            self.concurrency,
+            call_span,
+        )
+    }
+}
+
+/// Splits `start..end` into ranges of at most [`MAX_POINTS`] steps.
+///
+/// Each range ends where the next begins so the sample at each boundary is
+/// returned twice and must be de-duplicated.
+fn chunks(
+    start: DateTime<FixedOffset>,
+    end: DateTime<FixedOffset>,
+    step: f64,
+) -> Vec<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+    let span = (end - start).num_milliseconds() as f64 / 1000.0;
+
+    if step <= 0.0 || span / step <= MAX_POINTS {
+        return vec![(start, end)];
+    }
+
+    let chunk = TimeDelta::milliseconds((step * MAX_POINTS * 1000.0) as i64);
+
+    let mut chunks = vec![];
+    let mut chunk_start = start;
+
+    while chunk_start < end {
+        let chunk_end = (chunk_start + chunk).min(end);
+
+        chunks.push((chunk_start, chunk_end));
+
+        chunk_start = chunk_end;
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, FixedOffset, TimeDelta};
+
+    fn start() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2024-05-19T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn chunks_small() {
+        let end = start() + TimeDelta::hours(1);
+
+        assert_eq!(vec![(start(), end)], super::chunks(start(), end, 15.0));
+    }
+
+    #[test]
+    fn chunks_limit() {
+        let end = start() + TimeDelta::seconds(11_000 * 15);
+
+        assert_eq!(vec![(start(), end)], super::chunks(start(), end, 15.0));
+    }
+
+    #[test]
+    fn chunks_split() {
+        let end = start() + TimeDelta::weeks(1);
+
+        let chunks = super::chunks(start(), end, 15.0);
+
+        let chunk = TimeDelta::seconds(11_000 * 15);
 
-        QueryRange::new(query, query_span, self.flatten, call_span)
+        assert_eq!(4, chunks.len());
+        assert_eq!((start(), start() + chunk), chunks[0]);
+        assert_eq!((start() + chunk, start() + chunk * 2), chunks[1]);
+        assert_eq!((start() + chunk * 3, end), chunks[3]);
     }
 }
@@ -0,0 +1,157 @@
+use crate::{
+    Client,
+    client::{ApiRequest, response::deserialize_f64},
+    query::Timestamps,
+    signals::run_with_signal,
+};
+use nu_protocol::{
+    IntoInterruptiblePipelineData, LabeledError, PipelineData, Record, Signals, Span, Value, record,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct QueryExemplars {
+    query: ApiRequest,
+    query_span: Span,
+    timestamps: Timestamps,
+}
+
+/// A series and its exemplars
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExemplarSeries {
+    series_labels: HashMap<String, String>,
+    exemplars: Vec<Exemplar>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Exemplar {
+    labels: HashMap<String, String>,
+    #[serde(deserialize_with = "deserialize_f64")]
+    value: f64,
+    timestamp: f64,
+}
+
+impl QueryExemplars {
+    pub fn new(query: ApiRequest, query_span: Span, timestamps: Timestamps) -> Self {
+        Self {
+            query,
+            query_span,
+            timestamps,
+        }
+    }
+
+    pub fn run(self, signals: &Signals, call_span: Span) -> Result<PipelineData, LabeledError> {
+        let Self {
+            ref query,
+            query_span,
+            timestamps,
+        } = self;
+
+        self.runtime()?.block_on(async {
+            let series: Vec<ExemplarSeries> =
+                run_with_signal(signals, call_span, query.clone().get(query_span)).await??;
+
+            let exemplars = series
+                .into_iter()
+                .flat_map(move |series| series_to_values(series, timestamps, call_span))
+                .into_pipeline_data(call_span, signals.clone());
+
+            Ok(exemplars)
+        })
+    }
+}
+
+/// Returns one row per exemplar of `series`
+fn series_to_values(series: ExemplarSeries, timestamps: Timestamps, span: Span) -> Vec<Value> {
+    let name = series
+        .series_labels
+        .get("__name__")
+        .cloned()
+        .unwrap_or("[UNKNOWN]".to_string());
+
+    let labels = labels_to_value(&series.series_labels, span);
+
+    series
+        .exemplars
+        .into_iter()
+        .map(|exemplar| {
+            Value::record(
+                record! {
+                    "name" => Value::string(name.clone(), span),
+                    "labels" => labels.clone(),
+                    "exemplar_labels" => labels_to_value(&exemplar.labels, span),
+                    "value" => Value::float(exemplar.value, span),
+                    "timestamp" => timestamps.to_value(exemplar.timestamp, span),
+                },
+                span,
+            )
+        })
+        .collect()
+}
+
+fn labels_to_value(labels: &HashMap<String, String>, span: Span) -> Value {
+    let mut names: Vec<_> = labels.keys().filter(|name| *name != "__name__").collect();
+    names.sort();
+
+    let mut record = Record::new();
+
+    for name in names {
+        record.push(name, Value::string(&labels[name], span));
+    }
+
+    Value::record(record, span)
+}
+
+impl Client for QueryExemplars {}
+
+#[cfg(test)]
+mod test {
+    use super::ExemplarSeries;
+    use crate::query::Timestamps;
+    use nu_protocol::{Span, Value, record};
+
+    #[test]
+    fn series_to_values() {
+        let data = r#"[
+          {
+            "seriesLabels": {
+              "__name__": "test_exemplar_metric_total",
+              "instance": "localhost:8090",
+              "job": "prometheus",
+              "service": "bar"
+            },
+            "exemplars": [
+              {"labels": {"trace_id": "EpTxMJ40fUus7aGY"}, "value": "6", "timestamp": 1600096945.479},
+              {"labels": {"trace_id": "Olp9XHlq763ccsfa"}, "value": "19", "timestamp": 1600096955.479}
+            ]
+          }
+        ]"#;
+
+        let series: Vec<ExemplarSeries> = serde_json::from_str(data).unwrap();
+
+        let values = super::series_to_values(
+            series.into_iter().next().unwrap(),
+            Timestamps::Float,
+            Span::test_data(),
+        );
+
+        assert_eq!(2, values.len());
+
+        let expected = Value::test_record(record! {
+            "name" => Value::test_string("test_exemplar_metric_total"),
+            "labels" => Value::test_record(record! {
+                "instance" => Value::test_string("localhost:8090"),
+                "job" => Value::test_string("prometheus"),
+                "service" => Value::test_string("bar"),
+            }),
+            "exemplar_labels" => Value::test_record(record! {
+                "trace_id" => Value::test_string("Olp9XHlq763ccsfa"),
+            }),
+            "value" => Value::test_float(19.0),
+            "timestamp" => Value::test_float(1600096955.479),
+        });
+
+        assert_eq!(expected, values[1]);
+    }
+}
@@ -1,30 +1,32 @@
 use crate::{
     Client,
-    client::labeled_error,
-    query::{matrix_to_value, scalar_to_value, vector_to_value},
+    client::{Annotated, ApiRequest, Data, QueryResult},
+    query::{
+        QueryOptions, check_result_type, matrix_to_value, scalar_to_value, stats_to_value,
+        string_to_value, vector_to_value,
+    },
     signals::run_with_signal,
 };
 use nu_protocol::{IntoPipelineData, LabeledError, PipelineData, Signals, Span};
-use prometheus_http_query::{InstantQueryBuilder, response::Data};
 
 pub struct QueryInstant {
-    query: InstantQueryBuilder,
+    query: ApiRequest,
     query_span: Span,
-    flatten: bool,
+    options: QueryOptions,
     call_span: Span,
 }
 
 impl QueryInstant {
     pub fn new(
-        query: InstantQueryBuilder,
+        query: ApiRequest,
         query_span: Span,
-        flatten: bool,
+        options: QueryOptions,
         call_span: Span,
     ) -> Self {
         Self {
             query,
             query_span,
-            flatten,
+            options,
             call_span,
         }
     }
@@ -33,22 +35,41 @@ impl QueryInstant {
         let QueryInstant {
             ref query,
             query_span,
-            flatten,
+            options:
+                QueryOptions {
+                    flatten,
+                    timestamps,
+                    result_type,
+                    annotations,
+                },
             call_span,
         } = self;
 
         self.runtime()?.block_on(async {
-            let response = run_with_signal(signals, call_span, query.clone().get())
-                .await?
-                .map_err(|error| labeled_error(error, query_span))?;
-
-            let data = match response.into_inner().0 {
-                Data::Vector(v) => vector_to_value(v, flatten, call_span, signals),
-                Data::Matrix(m) => matrix_to_value(m, flatten, call_span, signals),
-                Data::Scalar(s) => scalar_to_value(&s, call_span).into_pipeline_data(),
+            let response: Annotated<QueryResult> =
+                run_with_signal(signals, call_span, query.clone().get_annotated(query_span))
+                    .await??;
+
+            let Annotated {
+                data: QueryResult { data, stats },
+                warnings,
+                infos,
+            } = response;
+
+            check_result_type(result_type, data.result_type(), query_span)?;
+
+            let data = match data {
+                Data::Vector(v) => vector_to_value(v, flatten, timestamps, call_span, signals),
+                Data::Matrix(m) => {
+                    matrix_to_value(vec![m], flatten, timestamps, call_span, signals)
+                }
+                Data::Scalar(s) => scalar_to_value(&s, timestamps, call_span).into_pipeline_data(),
+                Data::String(s) => string_to_value(&s, timestamps, call_span).into_pipeline_data(),
             };
 
-            Ok(data)
+            let stats = stats.map(|stats| stats_to_value(&stats, timestamps, call_span));
+
+            annotations.apply(data, warnings, infos, stats, query_span, call_span)
         })
     }
 }
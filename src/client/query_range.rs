@@ -1,51 +1,155 @@
 use crate::{
     Client,
-    client::labeled_error,
-    query::{matrix_to_value, scalar_to_value, vector_to_value},
+    client::{Annotated, ApiRequest, Data, QueryResult, Stats},
+    query::{
+        QueryOptions, check_result_type, matrix_to_value, scalar_to_value, stats_to_value,
+        string_to_value, vector_to_value,
+    },
     signals::run_with_signal,
 };
 use nu_protocol::{IntoPipelineData, LabeledError, PipelineData, Signals, Span};
-use prometheus_http_query::{RangeQueryBuilder, response::Data};
+use tokio::task::JoinSet;
 
 pub struct QueryRange {
-    query: RangeQueryBuilder,
+    queries: Vec<ApiRequest>,
     query_span: Span,
-    flatten: bool,
+    options: QueryOptions,
+    concurrency: usize,
     call_span: Span,
 }
 
 impl QueryRange {
-    pub fn new(query: RangeQueryBuilder, query_span: Span, flatten: bool, call_span: Span) -> Self {
+    pub fn new(
+        queries: Vec<ApiRequest>,
+        query_span: Span,
+        options: QueryOptions,
+        concurrency: usize,
+        call_span: Span,
+    ) -> Self {
         Self {
-            query,
+            queries,
             query_span,
-            flatten,
+            options,
+            concurrency,
             call_span,
         }
     }
 
     pub fn run(self, signals: &Signals) -> Result<PipelineData, LabeledError> {
+        let runtime = self.runtime()?;
+
         let QueryRange {
-            ref query,
+            queries,
             query_span,
-            flatten,
+            options:
+                QueryOptions {
+                    flatten,
+                    timestamps,
+                    result_type,
+                    annotations,
+                },
+            concurrency,
             call_span,
         } = self;
 
-        self.runtime()?.block_on(async {
-            let response = run_with_signal(signals, call_span, query.clone().get())
-                .await?
-                .map_err(|error| labeled_error(error, query_span))?;
+        runtime.block_on(async {
+            let responses = get_all(queries, concurrency, query_span, signals, call_span).await?;
+
+            let mut matrices = vec![];
+            let mut warnings = vec![];
+            let mut infos = vec![];
+            let mut stats: Option<Stats> = None;
+
+            let data = 'data: {
+                for response in responses {
+                    extend_unique(&mut warnings, response.warnings);
+                    extend_unique(&mut infos, response.infos);
+
+                    let QueryResult {
+                        data,
+                        stats: chunk_stats,
+                    } = response.data;
+
+                    match (&mut stats, chunk_stats) {
+                        (Some(stats), Some(chunk_stats)) => stats.merge(chunk_stats),
+                        (None, chunk_stats) => stats = chunk_stats,
+                        (Some(_), None) => {}
+                    }
+
+                    check_result_type(result_type, data.result_type(), query_span)?;
+
+                    match data {
+                        Data::Matrix(m) => matrices.push(m),
+                        Data::Vector(v) => {
+                            break 'data vector_to_value(
+                                v, flatten, timestamps, call_span, signals,
+                            );
+                        }
+                        Data::Scalar(s) => {
+                            break 'data scalar_to_value(&s, timestamps, call_span)
+                                .into_pipeline_data();
+                        }
+                        Data::String(s) => {
+                            break 'data string_to_value(&s, timestamps, call_span)
+                                .into_pipeline_data();
+                        }
+                    }
+                }
 
-            let pipeline = match response.into_inner().0 {
-                Data::Vector(v) => vector_to_value(v, flatten, call_span, signals),
-                Data::Matrix(m) => matrix_to_value(m, flatten, call_span, signals),
-                Data::Scalar(s) => scalar_to_value(&s, call_span).into_pipeline_data(),
+                matrix_to_value(matrices, flatten, timestamps, call_span, signals)
             };
 
-            Ok(pipeline)
+            let stats = stats.map(|stats| stats_to_value(&stats, timestamps, call_span));
+
+            annotations.apply(data, warnings, infos, stats, query_span, call_span)
         })
     }
 }
 
+/// Adds the warnings or infos from a chunk that earlier chunks did not return
+fn extend_unique(all: &mut Vec<String>, chunk: Vec<String>) {
+    for message in chunk {
+        if !all.contains(&message) {
+            all.push(message);
+        }
+    }
+}
+
+/// Sends `queries` with at most `concurrency` in flight, returning responses
+/// in the order of `queries`
+async fn get_all(
+    queries: Vec<ApiRequest>,
+    concurrency: usize,
+    query_span: Span,
+    signals: &Signals,
+    call_span: Span,
+) -> Result<Vec<Annotated<QueryResult>>, LabeledError> {
+    let mut responses: Vec<Option<Annotated<QueryResult>>> = queries.iter().map(|_| None).collect();
+    let mut queries = queries.into_iter().enumerate();
+    let mut tasks = JoinSet::new();
+
+    loop {
+        while tasks.len() < concurrency {
+            let Some((index, query)) = queries.next() else {
+                break;
+            };
+
+            tasks.spawn(async move { (index, query.get_annotated(query_span).await) });
+        }
+
+        // dropping tasks when interrupted aborts the remaining requests
+        let Some(joined) = run_with_signal(signals, call_span, tasks.join_next()).await? else {
+            break;
+        };
+
+        let (index, response) = joined.map_err(|e| {
+            LabeledError::new("Range query failed").with_label(e.to_string(), call_span)
+        })?;
+
+        responses[index] = Some(response?);
+    }
+
+    Ok(responses.into_iter().flatten().collect())
+}
+
 impl Client for QueryRange {}
@@ -0,0 +1,576 @@
+use nu_protocol::{LabeledError, Value};
+use prometheus_http_query::response::Sample;
+use serde::{Deserialize, Deserializer, de::Error};
+use std::{collections::HashMap, fmt};
+
+/// Result of an instant or range query.
+///
+/// Unlike [`prometheus_http_query::response::PromqlResult`] this includes
+/// string results.
+#[derive(Debug, Deserialize)]
+pub struct QueryResult {
+    #[serde(flatten)]
+    pub data: Data,
+    /// Present when the query was sent with `stats`
+    pub stats: Option<Stats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+pub enum Data {
+    Vector(Vec<InstantVector>),
+    Matrix(Vec<RangeVector>),
+    Scalar(Sample),
+    String(StringSample),
+}
+
+impl Data {
+    pub fn result_type(&self) -> ResultType {
+        match self {
+            Data::Vector(_) => ResultType::Vector,
+            Data::Matrix(_) => ResultType::Matrix,
+            Data::Scalar(_) => ResultType::Scalar,
+            Data::String(_) => ResultType::String,
+        }
+    }
+}
+
+/// Query statistics returned for `stats=all`
+#[derive(Debug, Default, Deserialize)]
+pub struct Stats {
+    pub timings: Timings,
+    pub samples: Samples,
+}
+
+impl Stats {
+    /// Adds the statistics of another chunk of a split range query.
+    ///
+    /// Samples for the step at the chunk boundary are only counted once.
+    pub fn merge(&mut self, other: Stats) {
+        let timings = &mut self.timings;
+
+        timings.eval_total_time += other.timings.eval_total_time;
+        timings.result_sort_time += other.timings.result_sort_time;
+        timings.query_preparation_time += other.timings.query_preparation_time;
+        timings.inner_eval_time += other.timings.inner_eval_time;
+        timings.exec_queue_time += other.timings.exec_queue_time;
+        timings.exec_total_time += other.timings.exec_total_time;
+
+        let samples = &mut self.samples;
+
+        samples.total_queryable_samples += other.samples.total_queryable_samples;
+        samples.peak_samples = samples.peak_samples.max(other.samples.peak_samples);
+
+        let last = samples
+            .total_queryable_samples_per_step
+            .last()
+            .map(|(timestamp, _)| *timestamp);
+
+        for (timestamp, count) in other.samples.total_queryable_samples_per_step {
+            if last.is_some_and(|last| timestamp <= last) {
+                // this step was also evaluated by the previous chunk
+                samples.total_queryable_samples -= count;
+            } else {
+                samples
+                    .total_queryable_samples_per_step
+                    .push((timestamp, count));
+            }
+        }
+    }
+}
+
+/// Query timings in seconds
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Timings {
+    pub eval_total_time: f64,
+    pub result_sort_time: f64,
+    pub query_preparation_time: f64,
+    pub inner_eval_time: f64,
+    pub exec_queue_time: f64,
+    pub exec_total_time: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Samples {
+    /// Pairs of step timestamp and samples queried for the step
+    pub total_queryable_samples_per_step: Vec<(f64, i64)>,
+    pub total_queryable_samples: i64,
+    pub peak_samples: i64,
+}
+
+/// A series and its sample at the query time
+#[derive(Debug, Deserialize)]
+pub struct InstantVector {
+    metric: HashMap<String, String>,
+    #[serde(flatten)]
+    point: Point,
+}
+
+impl InstantVector {
+    pub fn metric(&self) -> &HashMap<String, String> {
+        &self.metric
+    }
+
+    pub fn point(&self) -> &Point {
+        &self.point
+    }
+}
+
+/// A series and its samples over the query range.
+///
+/// A series may have both float and histogram samples if its type changed
+/// during the range.
+#[derive(Debug, Deserialize)]
+pub struct RangeVector {
+    metric: HashMap<String, String>,
+    #[serde(default)]
+    values: Vec<Sample>,
+    #[serde(default)]
+    histograms: Vec<HistogramSample>,
+}
+
+impl RangeVector {
+    /// Returns the metric and its float and histogram samples interleaved in
+    /// timestamp order
+    pub fn into_inner(self) -> (HashMap<String, String>, Vec<Point>) {
+        let mut points: Vec<Point> = self
+            .values
+            .into_iter()
+            .map(Point::Float)
+            .chain(self.histograms.into_iter().map(Point::Histogram))
+            .collect();
+
+        points.sort_by(|a, b| a.timestamp().total_cmp(&b.timestamp()));
+
+        (self.metric, points)
+    }
+}
+
+/// A float or native histogram sample
+#[derive(Debug, Deserialize)]
+pub enum Point {
+    #[serde(rename = "value")]
+    Float(Sample),
+    #[serde(rename = "histogram")]
+    Histogram(HistogramSample),
+}
+
+impl Point {
+    pub fn timestamp(&self) -> f64 {
+        match self {
+            Point::Float(sample) => sample.timestamp(),
+            Point::Histogram(sample) => sample.timestamp(),
+        }
+    }
+}
+
+/// A native histogram sample, sent as `[timestamp, {histogram}]`
+#[derive(Debug, Deserialize)]
+pub struct HistogramSample(f64, Histogram);
+
+impl HistogramSample {
+    pub fn timestamp(&self) -> f64 {
+        self.0
+    }
+
+    pub fn histogram(&self) -> &Histogram {
+        &self.1
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Histogram {
+    #[serde(deserialize_with = "deserialize_f64")]
+    count: f64,
+    #[serde(deserialize_with = "deserialize_f64")]
+    sum: f64,
+    #[serde(default)]
+    buckets: Vec<Bucket>,
+}
+
+impl Histogram {
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    pub fn buckets(&self) -> &[Bucket] {
+        &self.buckets
+    }
+
+    /// Infers the schema from the bucket boundaries.
+    ///
+    /// The query API does not include the schema, but the boundaries of every
+    /// exponential bucket are consecutive powers of `2^(2^-schema)`.  A schema
+    /// is only returned when every bucket outside the zero bucket fits the
+    /// same one, so custom bucket histograms return `None` unless all of their
+    /// boundaries lie on one exponential scale.
+    pub fn inferred_schema(&self) -> Option<i64> {
+        let mut buckets = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.lower != 0.0 && bucket.lower.signum() == bucket.upper.signum())
+            .peekable();
+
+        let schema = buckets.peek()?.schema()?;
+
+        buckets
+            .all(|bucket| bucket.schema() == Some(schema))
+            .then_some(schema)
+    }
+}
+
+/// A histogram bucket, sent as `[boundary_rule, "lower", "upper", "count"]`
+#[derive(Debug)]
+pub struct Bucket {
+    boundary_rule: u8,
+    lower: f64,
+    upper: f64,
+    count: f64,
+}
+
+impl Bucket {
+    /// The exponential schema this bucket belongs to, if any
+    fn schema(&self) -> Option<i64> {
+        let (lower, upper) = (self.lower.abs(), self.upper.abs());
+        let schema = -(lower.max(upper) / lower.min(upper)).log2().log2();
+        let rounded = schema.round();
+
+        if (schema - rounded).abs() >= 1e-6 || !(-4.0..=8.0).contains(&rounded) {
+            return None;
+        }
+
+        // The boundaries must also be powers of the base, not only their ratio
+        let index = upper.log2() * 2f64.powf(rounded);
+
+        ((index - index.round()).abs() < 1e-6).then_some(rounded as i64)
+    }
+
+    pub fn lower(&self) -> f64 {
+        self.lower
+    }
+
+    pub fn upper(&self) -> f64 {
+        self.upper
+    }
+
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Whether `lower` is included in the bucket
+    pub fn lower_inclusive(&self) -> bool {
+        self.boundary_rule == 1 || self.boundary_rule == 3
+    }
+
+    /// Whether `upper` is included in the bucket
+    pub fn upper_inclusive(&self) -> bool {
+        self.boundary_rule == 0 || self.boundary_rule == 3
+    }
+}
+
+impl<'de> Deserialize<'de> for Bucket {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (boundary_rule, lower, upper, count) =
+            <(u8, String, String, String)>::deserialize(deserializer)?;
+
+        if boundary_rule > 3 {
+            return Err(D::Error::custom(format!(
+                "invalid bucket boundary rule {boundary_rule}"
+            )));
+        }
+
+        Ok(Bucket {
+            boundary_rule,
+            lower: parse_f64::<D::Error>(&lower)?,
+            upper: parse_f64::<D::Error>(&upper)?,
+            count: parse_f64::<D::Error>(&count)?,
+        })
+    }
+}
+
+/// Deserializes a float sent as a string, like sample values
+pub fn deserialize_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_f64(&String::deserialize(deserializer)?)
+}
+
+fn parse_f64<E: Error>(value: &str) -> Result<f64, E> {
+    value
+        .parse()
+        .map_err(|_| E::custom(format!("invalid float {value:?}")))
+}
+
+/// A string result, sent as `[timestamp, "value"]`
+#[derive(Debug, Deserialize)]
+pub struct StringSample(f64, String);
+
+impl StringSample {
+    pub fn timestamp(&self) -> f64 {
+        self.0
+    }
+
+    pub fn value(&self) -> &str {
+        &self.1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResultType {
+    Vector,
+    Matrix,
+    Scalar,
+    String,
+}
+
+impl ResultType {
+    pub fn from_value(value: &Value) -> Result<Self, LabeledError> {
+        match value.as_str()? {
+            "vector" => Ok(ResultType::Vector),
+            "matrix" => Ok(ResultType::Matrix),
+            "scalar" => Ok(ResultType::Scalar),
+            "string" => Ok(ResultType::String),
+            _ => Err(LabeledError::new("Invalid result type")
+                .with_label("Must be vector, matrix, scalar, or string", value.span())),
+        }
+    }
+}
+
+impl fmt::Display for ResultType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResultType::Vector => "vector",
+            ResultType::Matrix => "matrix",
+            ResultType::Scalar => "scalar",
+            ResultType::String => "string",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Data, Histogram, InstantVector, Point, QueryResult, RangeVector, ResultType, Stats,
+    };
+    use nu_protocol::Value;
+    use rstest::rstest;
+
+    #[test]
+    fn query_result_string() {
+        let result: QueryResult =
+            serde_json::from_str(r#"{"resultType":"string","result":[1716956024.754,"foo"]}"#)
+                .unwrap();
+
+        let Data::String(sample) = result.data else {
+            unreachable!("expected string result");
+        };
+
+        assert_eq!(1716956024.754, sample.timestamp());
+        assert_eq!("foo", sample.value());
+    }
+
+    #[test]
+    fn query_result_stats() {
+        let result: QueryResult = serde_json::from_str(
+            r#"{
+              "resultType":"vector",
+              "result":[],
+              "stats":{
+                "timings":{
+                  "evalTotalTime":0.000158,
+                  "resultSortTime":0,
+                  "queryPreparationTime":0.000045,
+                  "innerEvalTime":0.000098,
+                  "execQueueTime":0.000012,
+                  "execTotalTime":0.000182
+                },
+                "samples":{
+                  "totalQueryableSamplesPerStep":[[1716956024.754,2]],
+                  "totalQueryableSamples":2,
+                  "peakSamples":2
+                }
+              }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(result.data, Data::Vector(_)));
+
+        let stats = result.stats.unwrap();
+
+        assert_eq!(0.000158, stats.timings.eval_total_time);
+        assert_eq!(0.000012, stats.timings.exec_queue_time);
+        assert_eq!(2, stats.samples.total_queryable_samples);
+        assert_eq!(
+            vec![(1716956024.754, 2)],
+            stats.samples.total_queryable_samples_per_step
+        );
+    }
+
+    #[test]
+    fn query_result_no_stats() {
+        let result: QueryResult =
+            serde_json::from_str(r#"{"resultType":"vector","result":[]}"#).unwrap();
+
+        assert!(result.stats.is_none());
+    }
+
+    #[test]
+    fn stats_merge() {
+        let mut stats: Stats = serde_json::from_str(
+            r#"{"timings":{"evalTotalTime":1.5},"samples":{"totalQueryableSamplesPerStep":[[0,1],[15,2]],"totalQueryableSamples":3,"peakSamples":2}}"#,
+        )
+        .unwrap();
+        let other: Stats = serde_json::from_str(
+            r#"{"timings":{"evalTotalTime":0.5},"samples":{"totalQueryableSamplesPerStep":[[15,2],[30,4]],"totalQueryableSamples":6,"peakSamples":4}}"#,
+        )
+        .unwrap();
+
+        stats.merge(other);
+
+        assert_eq!(2.0, stats.timings.eval_total_time);
+        assert_eq!(7, stats.samples.total_queryable_samples);
+        assert_eq!(4, stats.samples.peak_samples);
+        assert_eq!(
+            vec![(0.0, 1), (15.0, 2), (30.0, 4)],
+            stats.samples.total_queryable_samples_per_step
+        );
+    }
+
+    #[test]
+    fn instant_vector_histogram() {
+        let vector: InstantVector = serde_json::from_str(
+            r#"{"metric":{"__name__":"rpc_seconds"},"histogram":[1716956024.754,{"count":"6","sum":"1.5","buckets":[[3,"-0.001","0.001","2"],[0,"0.5","0.7071067811865475","4"]]}]}"#,
+        )
+        .unwrap();
+
+        let Point::Histogram(sample) = vector.point() else {
+            unreachable!("expected histogram sample");
+        };
+
+        let histogram = sample.histogram();
+
+        assert_eq!(1716956024.754, sample.timestamp());
+        assert_eq!(6.0, histogram.count());
+        assert_eq!(1.5, histogram.sum());
+        assert_eq!(Some(1), histogram.inferred_schema());
+
+        let zero = &histogram.buckets()[0];
+
+        assert_eq!(-0.001, zero.lower());
+        assert!(zero.lower_inclusive());
+        assert!(zero.upper_inclusive());
+
+        let bucket = &histogram.buckets()[1];
+
+        assert_eq!(0.5, bucket.lower());
+        assert_eq!(0.7071067811865475, bucket.upper());
+        assert!(!bucket.lower_inclusive());
+        assert!(bucket.upper_inclusive());
+        assert_eq!(4.0, bucket.count());
+    }
+
+    #[test]
+    fn instant_vector_float() {
+        let vector: InstantVector =
+            serde_json::from_str(r#"{"metric":{"__name__":"up"},"value":[1716956024.754,"1"]}"#)
+                .unwrap();
+
+        let Point::Float(sample) = vector.point() else {
+            unreachable!("expected float sample");
+        };
+
+        assert_eq!(1.0, sample.value());
+    }
+
+    #[test]
+    fn range_vector_interleave() {
+        let range: RangeVector = serde_json::from_str(
+            r#"{
+              "metric":{"__name__":"rpc_seconds"},
+              "values":[[0,"1"],[30,"2"]],
+              "histograms":[[15,{"count":"1","sum":"1"}],[45,{"count":"2","sum":"2"}]]
+            }"#,
+        )
+        .unwrap();
+
+        let (_, points) = range.into_inner();
+
+        let kinds: Vec<_> = points
+            .iter()
+            .map(|point| match point {
+                Point::Float(_) => ("float", point.timestamp()),
+                Point::Histogram(_) => ("histogram", point.timestamp()),
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("float", 0.0),
+                ("histogram", 15.0),
+                ("float", 30.0),
+                ("histogram", 45.0),
+            ],
+            kinds
+        );
+    }
+
+    #[rstest]
+    #[case(r#"[[0,"1","2","1"]]"#, Some(0))]
+    #[case(r#"[[0,"1","4","1"]]"#, Some(-1))]
+    #[case(r#"[[0,"-1.0905077326652577","-1","1"]]"#, Some(3))]
+    #[case(r#"[[0,"1","3","1"]]"#, None)]
+    #[case(r#"[[0,"3","6","1"]]"#, None)]
+    #[case(r#"[[0,"1","2","1"],[0,"2","4","1"]]"#, Some(0))]
+    #[case(r#"[[0,"1","2","1"],[0,"2","5","1"],[0,"5","10","1"]]"#, None)]
+    #[case(r#"[[3,"-0.001","0.001","1"]]"#, None)]
+    #[case(r#"[]"#, None)]
+    fn histogram_inferred_schema(#[case] buckets: &str, #[case] expected: Option<i64>) {
+        let histogram: Histogram =
+            serde_json::from_str(&format!(r#"{{"count":"1","sum":"1","buckets":{buckets}}}"#))
+                .unwrap();
+
+        assert_eq!(expected, histogram.inferred_schema());
+    }
+
+    #[test]
+    fn bucket_invalid() {
+        let err = serde_json::from_str::<Histogram>(
+            r#"{"count":"1","sum":"1","buckets":[[4,"1","2","1"]]}"#,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("invalid bucket boundary rule 4"));
+    }
+
+    #[rstest]
+    #[case("vector", ResultType::Vector)]
+    #[case("matrix", ResultType::Matrix)]
+    #[case("scalar", ResultType::Scalar)]
+    #[case("string", ResultType::String)]
+    fn result_type_from_value(#[case] input: &str, #[case] expected: ResultType) {
+        let result_type = ResultType::from_value(&Value::test_string(input)).unwrap();
+
+        assert_eq!(expected, result_type);
+        assert_eq!(input, result_type.to_string());
+    }
+
+    #[test]
+    fn result_type_from_value_invalid() {
+        let err = ResultType::from_value(&Value::test_string("table")).unwrap_err();
+
+        assert_eq!("Invalid result type", err.msg);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Client, client::ApiRequest, signals::run_with_signal, time::rfc3339_to_value};
+use nu_protocol::{
+    IntoInterruptiblePipelineData, LabeledError, PipelineData, Record, Signals, Span, Value, record,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct Rules {
+    request: ApiRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleDiscovery {
+    groups: Vec<RuleGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleGroup {
+    name: String,
+    file: String,
+    rules: Vec<Rule>,
+}
+
+/// A recording or alerting rule
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    name: String,
+    query: String,
+    #[serde(default)]
+    labels: HashMap<String, String>,
+    health: String,
+    #[serde(default)]
+    last_error: String,
+    evaluation_time: f64,
+    last_evaluation: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+impl Rules {
+    pub fn new(request: ApiRequest) -> Self {
+        Self { request }
+    }
+
+    pub fn run(self, signals: &Signals, span: Span) -> Result<PipelineData, LabeledError> {
+        let Self { ref request } = self;
+
+        self.runtime()?.block_on(async {
+            let discovery: RuleDiscovery =
+                run_with_signal(signals, span, request.clone().get(span)).await??;
+
+            let rules = discovery
+                .groups
+                .into_iter()
+                .flat_map(move |group| group_to_values(group, span))
+                .into_pipeline_data(span, signals.clone());
+
+            Ok(rules)
+        })
+    }
+}
+
+impl Client for Rules {}
+
+/// Returns one row per rule in `group`
+fn group_to_values(group: RuleGroup, span: Span) -> Vec<Value> {
+    let RuleGroup { name, file, rules } = group;
+
+    rules
+        .into_iter()
+        .map(|rule| {
+            let mut names: Vec<_> = rule.labels.keys().collect();
+            names.sort();
+
+            let mut labels = Record::new();
+
+            for label in names {
+                labels.push(label, Value::string(&rule.labels[label], span));
+            }
+
+            let record = record! {
+                "group" => Value::string(&name, span),
+                "file" => Value::string(&file, span),
+                "type" => Value::string(rule.kind, span),
+                "name" => Value::string(rule.name, span),
+                "query" => Value::string(rule.query, span),
+                "labels" => Value::record(labels, span),
+                "health" => Value::string(rule.health, span),
+                "last_error" => Value::string(rule.last_error, span),
+                "evaluation_time" => Value::duration((rule.evaluation_time * 1_000_000_000.0) as i64, span),
+                "last_evaluation" => rfc3339_to_value(&rule.last_evaluation, span),
+            };
+
+            Value::record(record, span)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::RuleDiscovery;
+    use nu_protocol::{Span, Value, record};
+
+    #[test]
+    fn group_to_values() {
+        let data = r#"{
+          "groups": [
+            {
+              "name": "example",
+              "file": "/rules.yaml",
+              "interval": 60,
+              "limit": 0,
+              "evaluationTime": 0.000186951,
+              "lastEvaluation": "2024-05-19T05:20:32.5Z",
+              "rules": [
+                {
+                  "state": "firing",
+                  "name": "HighRequestLatency",
+                  "query": "job:request_latency_seconds:mean5m{job=\"myjob\"} > 0.5",
+                  "duration": 600,
+                  "labels": {"severity": "page"},
+                  "annotations": {"summary": "High request latency"},
+                  "alerts": [],
+                  "health": "ok",
+                  "evaluationTime": 0.000312805,
+                  "lastEvaluation": "2024-05-19T05:20:32.5Z",
+                  "type": "alerting"
+                },
+                {
+                  "name": "job:http_inprogress_requests:sum",
+                  "query": "sum by (job) (http_inprogress_requests)",
+                  "health": "err",
+                  "lastError": "query timed out",
+                  "evaluationTime": 0.5,
+                  "lastEvaluation": "0001-01-01T00:00:00Z",
+                  "type": "recording"
+                }
+              ]
+            }
+          ]
+        }"#;
+
+        let discovery: RuleDiscovery = serde_json::from_str(data).unwrap();
+
+        let values = super::group_to_values(
+            discovery.groups.into_iter().next().unwrap(),
+            Span::test_data(),
+        );
+
+        assert_eq!(2, values.len());
+
+        let alerting = values[0].as_record().unwrap();
+
+        assert_eq!("alerting", alerting.get("type").unwrap().as_str().unwrap());
+        assert_eq!(
+            Value::test_record(record! { "severity" => Value::test_string("page") }),
+            alerting.get("labels").unwrap().clone()
+        );
+        assert_eq!(
+            "2024-05-19T05:20:32.500+00:00",
+            alerting
+                .get("last_evaluation")
+                .unwrap()
+                .as_date()
+                .unwrap()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+        );
+
+        let expected = Value::test_record(record! {
+            "group" => Value::test_string("example"),
+            "file" => Value::test_string("/rules.yaml"),
+            "type" => Value::test_string("recording"),
+            "name" => Value::test_string("job:http_inprogress_requests:sum"),
+            "query" => Value::test_string("sum by (job) (http_inprogress_requests)"),
+            "labels" => Value::test_record(record! {}),
+            "health" => Value::test_string("err"),
+            "last_error" => Value::test_string("query timed out"),
+            "evaluation_time" => Value::test_duration(500_000_000),
+            "last_evaluation" => Value::test_date(
+                chrono::DateTime::parse_from_rfc3339("0001-01-01T00:00:00Z").unwrap()
+            ),
+        });
+
+        assert_eq!(expected, values[1]);
+    }
+}
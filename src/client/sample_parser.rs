@@ -0,0 +1,464 @@
+use crate::client::ParseFormat;
+use chrono::{DateTime, FixedOffset};
+use nom::{
+    IResult, Offset, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{anychar, char, none_of, space1},
+    combinator::{all_consuming, map, opt, recognize},
+    multi::{many0, separated_list0},
+    sequence::{delimited, preceded, separated_pair},
+};
+use nom_language::error::VerboseError;
+use nu_protocol::{LabeledError, Span};
+use std::borrow::Cow;
+
+/// The timestamp and exemplar of a sample, which the exposition parser does
+/// not read
+#[derive(Debug, Default, PartialEq)]
+pub struct SampleExtras {
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub exemplar: Option<Exemplar>,
+}
+
+/// An OpenMetrics exemplar
+#[derive(Debug, PartialEq)]
+pub struct Exemplar {
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+    pub timestamp: Option<DateTime<FixedOffset>>,
+}
+
+/// A sample line split into the series, the whitespace separating the value,
+/// the value, and the timestamp and exemplar text
+type SampleLine<'a> = (
+    &'a str,
+    &'a str,
+    &'a str,
+    Option<&'a str>,
+    Option<ExemplarText<'a>>,
+);
+
+type ExemplarText<'a> = (Vec<(&'a str, String)>, &'a str, Option<&'a str>);
+
+/// A line of an exposition, with the timestamp and exemplar of a sample
+/// removed
+#[derive(Debug, PartialEq)]
+pub struct Line<'a> {
+    /// Line number, from 1
+    pub number: usize,
+    /// Byte offset of the line in the input
+    pub offset: usize,
+    /// The line without its newline
+    pub original: &'a str,
+    /// The part of the line the exposition parser reads
+    pub text: Cow<'a, str>,
+    pub newline: bool,
+    /// The extras of a sample line
+    pub extras: Option<SampleExtras>,
+    /// The offset in `text` of the value, and the bytes removed before it,
+    /// when the whitespace before the value was replaced by a single space
+    pub collapsed: Option<(usize, usize)>,
+}
+
+impl Line<'_> {
+    /// The byte offset in `original` of a byte offset in `text`
+    pub fn original_offset(&self, offset: usize) -> usize {
+        match self.collapsed {
+            Some((at, removed)) if offset >= at => offset + removed,
+            _ => offset,
+        }
+    }
+}
+
+/// An error in a line of an exposition
+#[derive(Debug, PartialEq)]
+pub struct LineError {
+    pub line: usize,
+    /// Character column, from 1
+    pub column: usize,
+    /// Byte offset of the error in the input
+    pub offset: usize,
+    /// Byte length of the error
+    pub len: usize,
+    /// The line containing the error
+    pub text: String,
+    pub message: String,
+}
+
+impl LineError {
+    /// An error at byte `column` of a line through the end of the line
+    pub fn new(
+        number: usize,
+        offset: usize,
+        line: &str,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        let column = column.min(line.len());
+
+        Self {
+            line: number,
+            column: line[..column].chars().count() + 1,
+            offset: offset + column,
+            len: line.len() - column,
+            text: line.into(),
+            message: message.into(),
+        }
+    }
+
+    /// A `LabeledError` labeling the error within the `span` of the input.
+    ///
+    /// When the error lies outside the span the whole span is labeled.
+    pub fn labeled_error(&self, span: Span) -> LabeledError {
+        let start = span.start + self.offset;
+        let end = start + self.len;
+
+        let span = if end <= span.end {
+            Span::new(start, end)
+        } else {
+            span
+        };
+
+        LabeledError::new("Metrics parse error")
+            .with_label(
+                format!(
+                    "line {}, column {}: {}",
+                    self.line, self.column, self.message
+                ),
+                span,
+            )
+            .with_help("Use --lenient to skip invalid lines")
+    }
+}
+
+/// Splits `text` into lines, removing timestamps and exemplars from samples.
+///
+/// The exposition parser only accepts a single space before the value of a
+/// sample, so other runs of spaces and tabs are replaced by one.  Lines that
+/// are not samples are left for the exposition parser to report.
+pub fn lines(text: &str, format: ParseFormat) -> Vec<Result<Line<'_>, LineError>> {
+    let mut offset = 0;
+
+    text.split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let line_offset = offset;
+            offset += line.len();
+
+            let (original, newline) = match line.strip_suffix('\n') {
+                Some(original) => (original, true),
+                None => (line, false),
+            };
+
+            let mut line = Line {
+                number: index + 1,
+                offset: line_offset,
+                original,
+                text: original.into(),
+                newline,
+                extras: None,
+                collapsed: None,
+            };
+
+            if original.is_empty() || original.starts_with('#') {
+                return Ok(line);
+            }
+
+            let Ok((_, (series, separator, value, timestamp, exemplar))) = sample_line(original)
+            else {
+                line.extras = Some(SampleExtras::default());
+
+                return Ok(line);
+            };
+
+            let error = |at: &str, message: String| {
+                LineError::new(
+                    line.number,
+                    line.offset,
+                    original,
+                    original.offset(at),
+                    message,
+                )
+            };
+
+            let timestamp = timestamp
+                .map(|timestamp| {
+                    parse_timestamp(timestamp, format)
+                        .ok_or_else(|| error(timestamp, format!("invalid timestamp {timestamp:?}")))
+                })
+                .transpose()?;
+
+            let exemplar = exemplar
+                .map(|(labels, value, timestamp)| {
+                    let value = value
+                        .parse()
+                        .map_err(|_| error(value, format!("invalid exemplar value {value:?}")))?;
+
+                    let timestamp = timestamp
+                        .map(|timestamp| {
+                            parse_timestamp(timestamp, ParseFormat::Openmetrics).ok_or_else(|| {
+                                error(
+                                    timestamp,
+                                    format!("invalid exemplar timestamp {timestamp:?}"),
+                                )
+                            })
+                        })
+                        .transpose()?;
+
+                    let labels = labels
+                        .into_iter()
+                        .map(|(name, value)| (name.to_string(), value))
+                        .collect();
+
+                    Ok(Exemplar {
+                        labels,
+                        value,
+                        timestamp,
+                    })
+                })
+                .transpose()?;
+
+            line.text = if separator == " " {
+                original[..original.offset(value) + value.len()].into()
+            } else {
+                line.collapsed = Some((series.len() + 1, separator.len() - 1));
+
+                format!("{series} {value}").into()
+            };
+            line.extras = Some(SampleExtras {
+                timestamp,
+                exemplar,
+            });
+
+            Ok(line)
+        })
+        .collect()
+}
+
+/// Parses a timestamp, milliseconds in the Prometheus format and seconds in
+/// the OpenMetrics format
+fn parse_timestamp(timestamp: &str, format: ParseFormat) -> Option<DateTime<FixedOffset>> {
+    let parsed = match format {
+        ParseFormat::Prometheus => timestamp
+            .parse()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis),
+        ParseFormat::Openmetrics => timestamp
+            .parse::<f64>()
+            .ok()
+            .filter(|seconds| seconds.is_finite())
+            .and_then(|seconds| {
+                DateTime::from_timestamp(
+                    seconds.floor() as i64,
+                    ((seconds - seconds.floor()) * 1e9)
+                        .round()
+                        .min(999_999_999.0) as u32,
+                )
+            }),
+    };
+
+    parsed.map(|timestamp| timestamp.fixed_offset())
+}
+
+fn sample_line(input: &str) -> IResult<&str, SampleLine<'_>, VerboseError<&str>> {
+    all_consuming((
+        recognize((metric_name, opt(labels))),
+        space1,
+        token,
+        opt(preceded(space1, token)),
+        opt(preceded((space1, tag("#"), space1), exemplar)),
+    ))
+    .parse(input)
+}
+
+fn exemplar(input: &str) -> IResult<&str, ExemplarText<'_>, VerboseError<&str>> {
+    (
+        delimited(
+            char('{'),
+            separated_list0(
+                char(','),
+                separated_pair(metric_name, char('='), label_value),
+            ),
+            char('}'),
+        ),
+        preceded(space1, token),
+        opt(preceded(space1, token)),
+    )
+        .parse(input)
+}
+
+/// Matches `[a-zA-Z_:][a-zA-Z0-9_:]*`, loosely
+fn metric_name(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == ':').parse(input)
+}
+
+/// Matches a label set without interpreting it
+fn labels(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize((
+        char('{'),
+        many0(alt((recognize(label_value), recognize(none_of("\"}"))))),
+        char('}'),
+    ))
+    .parse(input)
+}
+
+/// Matches a quoted label value, unescaping it
+fn label_value(input: &str) -> IResult<&str, String, VerboseError<&str>> {
+    let escape = map(anychar, |c| match c {
+        'n' => '\n',
+        c => c,
+    });
+
+    map(
+        delimited(
+            char('"'),
+            many0(alt((preceded(char('\\'), escape), none_of("\\\"")))),
+            char('"'),
+        ),
+        |chars| chars.into_iter().collect(),
+    )
+    .parse(input)
+}
+
+/// Matches a value or timestamp
+fn token(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    take_while1(|c| c != ' ' && c != '\t' && c != '#').parse(input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Exemplar, LineError, SampleExtras};
+    use crate::client::ParseFormat;
+    use chrono::DateTime;
+    use nu_protocol::Span;
+
+    #[test]
+    fn lines() {
+        let input = r#"# TYPE a counter
+a_total{path="/ #{x}"} 1 1700000000.5 # {trace_id="a\"b"} 2.5 1700000000
+b 2
+c{x="y"} 3 # {} 4
+"#;
+
+        let lines: Vec<_> = super::lines(input, ParseFormat::Openmetrics)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        let text: Vec<_> = lines.iter().map(|line| line.text.as_ref()).collect();
+
+        assert_eq!(
+            vec![
+                "# TYPE a counter",
+                r#"a_total{path="/ #{x}"} 1"#,
+                "b 2",
+                r#"c{x="y"} 3"#
+            ],
+            text
+        );
+
+        let timestamp = |millis| {
+            Some(
+                DateTime::from_timestamp_millis(millis)
+                    .unwrap()
+                    .fixed_offset(),
+            )
+        };
+
+        let extras: Vec<_> = lines.into_iter().map(|line| line.extras).collect();
+
+        assert_eq!(
+            vec![
+                None,
+                Some(SampleExtras {
+                    timestamp: timestamp(1_700_000_000_500),
+                    exemplar: Some(Exemplar {
+                        labels: vec![("trace_id".into(), "a\"b".into())],
+                        value: 2.5,
+                        timestamp: timestamp(1_700_000_000_000),
+                    }),
+                }),
+                Some(SampleExtras::default()),
+                Some(SampleExtras {
+                    timestamp: None,
+                    exemplar: Some(Exemplar {
+                        labels: vec![],
+                        value: 4.0,
+                        timestamp: None,
+                    }),
+                }),
+            ],
+            extras
+        );
+    }
+
+    #[test]
+    fn lines_prometheus_timestamp() {
+        let line = super::lines("up 1 1700000000123\n", ParseFormat::Prometheus)
+            .pop()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!("up 1", line.text);
+        assert_eq!(
+            DateTime::from_timestamp_millis(1_700_000_000_123).map(|t| t.fixed_offset()),
+            line.extras.unwrap().timestamp
+        );
+    }
+
+    #[test]
+    fn lines_whitespace() {
+        let line = super::lines(
+            "up{job=\"a b\"}\t 1  \t1700000000123\n",
+            ParseFormat::Prometheus,
+        )
+        .pop()
+        .unwrap()
+        .unwrap();
+
+        assert_eq!("up{job=\"a b\"} 1", line.text);
+        assert_eq!(Some((14, 1)), line.collapsed);
+        assert_eq!(13, line.original_offset(13));
+        assert_eq!(16, line.original_offset(15));
+        assert_eq!(
+            DateTime::from_timestamp_millis(1_700_000_000_123).map(|t| t.fixed_offset()),
+            line.extras.unwrap().timestamp
+        );
+    }
+
+    #[test]
+    fn lines_invalid_timestamp() {
+        let error = super::lines("up 1\nup 1 soon\n", ParseFormat::Prometheus)
+            .pop()
+            .unwrap()
+            .unwrap_err();
+
+        let expected = LineError {
+            line: 2,
+            column: 6,
+            offset: 10,
+            len: 4,
+            text: "up 1 soon".into(),
+            message: "invalid timestamp \"soon\"".into(),
+        };
+
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn labeled_error() {
+        let error = LineError::new(2, 5, "up{x=\"☃\"} one", 12, "bad");
+
+        assert_eq!(11, error.column);
+
+        let labeled = error.labeled_error(Span::new(100, 200));
+
+        assert_eq!("line 2, column 11: bad", labeled.labels[0].text);
+        assert_eq!(Span::new(117, 120), labeled.labels[0].span);
+
+        let labeled = error.labeled_error(Span::new(100, 110));
+
+        assert_eq!(Span::new(100, 110), labeled.labels[0].span);
+    }
+}
@@ -1,58 +1,67 @@
-use nom_openmetrics::{Family, Sample};
-use nu_protocol::{LabeledError, Record, Span, Value};
+use nu_protocol::{LabeledError, Span, Value};
 
-use crate::client::Client;
+use crate::client::{Client, Parse};
 
 pub struct Scrape {
     target: String,
+    span: Span,
+    flatten: bool,
+    lenient: bool,
 }
 
 impl Scrape {
-    pub fn new(target: String) -> Self {
-        Self { target }
+    pub fn new(target: String, span: Span) -> Self {
+        Self {
+            target,
+            span,
+            flatten: true,
+            lenient: false,
+        }
     }
 
     pub fn run(self) -> Result<Value, LabeledError> {
-        let Self { ref target, .. } = self;
+        let Self {
+            ref target,
+            span,
+            flatten,
+            lenient,
+        } = self;
 
         self.runtime()?.block_on(async {
-            let body = reqwest::get(target).await.unwrap().bytes().await.unwrap();
+            let response = reqwest::get(target)
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| LabeledError::new("Scrape error").with_label(e.to_string(), span))?;
 
-            let body = String::from_utf8(body.to_vec()).unwrap();
+            let body = response
+                .bytes()
+                .await
+                .map_err(|e| LabeledError::new("Scrape error").with_label(e.to_string(), span))?;
 
-            let (_, families) = nom_openmetrics::parser::prometheus(&body).unwrap();
+            let body = String::from_utf8(body.to_vec()).map_err(|e| {
+                LabeledError::new("Invalid scrape response").with_label(e.to_string(), span)
+            })?;
 
-            let families = families
-                .iter()
-                .map(|family| family_to_value(family))
-                .collect();
+            let body = Value::string(body, span);
 
-            Ok(Value::list(families, Span::unknown()))
+            let mut parser = Parse::new(&body);
+            parser.set_flatten(flatten);
+            parser.set_lenient(lenient);
+
+            parser.run()
         })
     }
-}
-
-impl Client for Scrape {}
-
-fn family_to_value(family: &Family) -> Value {
-    let mut record = Record::new();
-
-    let samples = family
-        .samples
-        .iter()
-        .map(|sample| sample_to_value(sample))
-        .collect();
 
-    record.insert("samples", Value::list(samples, Span::unknown()));
+    /// Place sample labels in a "labels" column when `flatten` is false
+    pub fn set_flatten(&mut self, flatten: bool) {
+        self.flatten = flatten;
+    }
 
-    Value::record(record, Span::unknown())
+    /// Skip invalid lines, reporting them in an "errors" column, when
+    /// `lenient` is true
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
 }
 
-fn sample_to_value(sample: &Sample) -> Value {
-    let mut record = Record::new();
-
-    record.insert("name", Value::string(sample.name(), Span::unknown()));
-    record.insert("value", Value::float(sample.number(), Span::unknown()));
-
-    Value::record(record, Span::unknown())
-}
+impl Client for Scrape {}
@@ -1,3 +1,4 @@
+use crate::exposition::escape_label_value;
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_till, take_while, take_while1},
@@ -10,6 +11,7 @@ use nom::{
 use nom_language::error::{VerboseError, VerboseErrorKind};
 use nu_protocol::{LabeledError, Span, Value};
 use prometheus_http_query::Selector;
+use std::fmt;
 
 pub struct SelectorParser {}
 
@@ -18,16 +20,84 @@ impl SelectorParser {
         let span = input.span();
         let input = input.as_str()?;
 
-        let (_, selector) = selector(input).map_err(|error| {
-            if let nom::Err::Error(error) = error {
-                nom_error_to_nu_error(input, error, span)
-            } else {
-                LabeledError::new("Selector parse error").with_label("selector", span)
-            }
-        })?;
+        let (_, selector) = selector(input).map_err(|error| parse_error(input, error, span))?;
 
         Ok(selector)
     }
+
+    /// Parses a selector into label matchers for an Alertmanager.
+    ///
+    /// Alertmanager alerts have no metric name, so a selector with a metric
+    /// name or a `__name__` matcher is rejected.
+    pub fn matchers(input: &Value) -> Result<Vec<Matcher>, LabeledError> {
+        let span = input.span();
+        let input = input.as_str()?;
+
+        let (_, matchers) = matchers(input).map_err(|error| parse_error(input, error, span))?;
+
+        if matchers.iter().any(|matcher| matcher.label == "__name__") {
+            return Err(LabeledError::new("Invalid selector")
+                .with_label("Alertmanager alerts have no metric name", span)
+                .with_help(
+                    "Match the alertname label instead, like {alertname=\"InstanceDown\"}",
+                ));
+        }
+
+        Ok(matchers.into_iter().map(Matcher::from).collect())
+    }
+}
+
+/// A label matcher from a selector
+#[derive(Debug, PartialEq)]
+pub struct Matcher {
+    pub name: String,
+    pub value: String,
+    pub is_regex: bool,
+    pub is_equal: bool,
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match (self.is_regex, self.is_equal) {
+            (false, true) => "=",
+            (false, false) => "!=",
+            (true, true) => "=~",
+            (true, false) => "!~",
+        };
+
+        write!(
+            f,
+            "{}{operation}\"{}\"",
+            self.name,
+            escape_label_value(&self.value)
+        )
+    }
+}
+
+impl From<LabelMatcher<'_>> for Matcher {
+    fn from(matcher: LabelMatcher<'_>) -> Self {
+        let (is_regex, is_equal) = match matcher.operation {
+            Operation::Eq => (false, true),
+            Operation::Ne => (false, false),
+            Operation::RegexEq => (true, true),
+            Operation::RegexNe => (true, false),
+        };
+
+        Self {
+            name: matcher.label.into(),
+            value: matcher.value.into(),
+            is_regex,
+            is_equal,
+        }
+    }
+}
+
+fn parse_error(input: &str, error: nom::Err<VerboseError<&str>>, span: Span) -> LabeledError {
+    if let nom::Err::Error(error) = error {
+        nom_error_to_nu_error(input, error, span)
+    } else {
+        LabeledError::new("Selector parse error").with_label("selector", span)
+    }
 }
 
 fn nom_error_to_nu_error(input: &str, error: VerboseError<&str>, span: Span) -> LabeledError {
@@ -176,6 +246,32 @@ fn metric_name(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     .parse(input)
 }
 
+fn matchers(input: &'_ str) -> IResult<&'_ str, Vec<LabelMatcher<'_>>, VerboseError<&'_ str>> {
+    let name = |value| LabelMatcher {
+        label: "__name__",
+        operation: Operation::Eq,
+        value,
+    };
+
+    context(
+        "selector",
+        complete(terminated(
+            alt((
+                labels,
+                map(label, |label_matcher| vec![label_matcher]),
+                map((metric_name, labels), |(metric, labels)| {
+                    let mut matchers = vec![name(metric)];
+                    matchers.extend(labels);
+                    matchers
+                }),
+                map(metric_name, |metric| vec![name(metric)]),
+            )),
+            eof,
+        )),
+    )
+    .parse(input)
+}
+
 fn operation(input: &str) -> IResult<&str, Operation, VerboseError<&str>> {
     context(
         "operation",
@@ -239,6 +335,79 @@ mod test {
         assert_eq!(Selector::new().eq("label", "value"), metric);
     }
 
+    #[rstest]
+    #[case(r#"alertname="Down""#, vec![("alertname", "Down", false, true)])]
+    #[case(r#"job!~"p.+""#, vec![("job", "p.+", true, false)])]
+    #[case(
+        r#"{job!="prometheus",env=~"prod"}"#,
+        vec![("job", "prometheus", false, false), ("env", "prod", true, true)]
+    )]
+    #[case(r#"{}"#, vec![])]
+    fn matchers(#[case] input: &str, #[case] expected: Vec<(&str, &str, bool, bool)>) {
+        let value = Value::string(input, Span::unknown());
+
+        let matchers = SelectorParser::matchers(&value).unwrap();
+
+        let expected: Vec<Matcher> = expected
+            .into_iter()
+            .map(|(name, value, is_regex, is_equal)| Matcher {
+                name: name.into(),
+                value: value.into(),
+                is_regex,
+                is_equal,
+            })
+            .collect();
+
+        assert_eq!(expected, matchers);
+    }
+
+    #[test]
+    fn matcher_display() {
+        let value = Value::string(r#"{job!~"p.+"}"#, Span::unknown());
+
+        let matchers = SelectorParser::matchers(&value).unwrap();
+
+        assert_eq!(r#"job!~"p.+""#, matchers[0].to_string());
+    }
+
+    #[test]
+    fn matcher_display_escape() {
+        let matcher = Matcher {
+            name: "path".into(),
+            value: r#"C:\"x""#.into(),
+            is_regex: false,
+            is_equal: true,
+        };
+
+        assert_eq!(r#"path="C:\\\"x\"""#, matcher.to_string());
+    }
+
+    #[test]
+    fn matchers_error() {
+        let value = Value::string(r#"up{job="prometheus"} junk"#, Span::unknown());
+
+        let error = SelectorParser::matchers(&value).unwrap_err();
+
+        assert_eq!("Selector parse error", error.msg);
+    }
+
+    #[rstest]
+    #[case("up")]
+    #[case(r#"up{job="node"}"#)]
+    #[case(r#"{__name__="up"}"#)]
+    fn matchers_metric_name(#[case] input: &str) {
+        let value = Value::string(input, Span::new(5, 5 + input.len()));
+
+        let error = SelectorParser::matchers(&value).unwrap_err();
+
+        assert_eq!("Invalid selector", error.msg);
+        assert_eq!(
+            "Alertmanager alerts have no metric name",
+            error.labels[0].text
+        );
+        assert_eq!(Span::new(5, 5 + input.len()), error.labels[0].span);
+    }
+
     #[test]
     fn metric() {
         let input = Value::string("metric", Span::unknown());
@@ -364,6 +533,7 @@ mod test {
         let engine = EngineState::default();
         let mut working_set = StateWorkingSet::new(&engine);
 
+        #[allow(clippy::useless_conversion)]
         let file_id = working_set.add_file("input".into(), input.as_bytes());
         let span = working_set.get_span_for_file(file_id);
 
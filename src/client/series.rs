@@ -1,16 +1,16 @@
-use crate::{Client, client::labeled_error, signals::run_with_signal};
+use crate::{Client, client::ApiRequest, signals::run_with_signal};
 use nu_protocol::{
     IntoInterruptiblePipelineData, LabeledError, PipelineData, Signals, Span, Value, record,
 };
-use prometheus_http_query::SeriesQueryBuilder;
+use std::collections::HashMap;
 
 pub struct Series {
-    builder: SeriesQueryBuilder,
+    builder: ApiRequest,
     span: Span,
 }
 
 impl Series {
-    pub fn new(builder: SeriesQueryBuilder, span: Span) -> Self {
+    pub fn new(builder: ApiRequest, span: Span) -> Self {
         Self { builder, span }
     }
 
@@ -21,9 +21,8 @@ impl Series {
         } = self;
 
         self.runtime()?.block_on(async {
-            let series = run_with_signal(signals, span, builder.clone().get())
-                .await?
-                .map_err(|error| labeled_error(error, selector_span))?;
+            let series: Vec<HashMap<String, String>> =
+                run_with_signal(signals, span, builder.clone().get(selector_span)).await??;
 
             let result = series
                 .into_iter()
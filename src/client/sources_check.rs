@@ -0,0 +1,325 @@
+use crate::{
+    Client,
+    client::api_url,
+    signals::run_with_signal,
+    source::{Source, error_message},
+};
+use chrono::{DateTime, FixedOffset};
+use nu_protocol::{
+    IntoInterruptiblePipelineData, LabeledError, PipelineData, Signals, Span, Value, record,
+};
+use reqwest::tls::TlsInfo;
+use std::time::{Duration, Instant};
+
+/// A source to check by name, or the error that prevented loading it
+pub type Checked = (Option<String>, Result<Source, LabeledError>);
+
+pub struct SourcesCheck {
+    sources: Vec<Checked>,
+}
+
+impl SourcesCheck {
+    pub fn new(sources: Vec<Checked>) -> Self {
+        Self { sources }
+    }
+
+    pub fn run(self, signals: &Signals, span: Span) -> Result<PipelineData, LabeledError> {
+        let runtime = self.runtime()?;
+
+        let Self { sources } = self;
+
+        runtime.block_on(async {
+            let mut checks = vec![];
+
+            for (name, source) in sources {
+                let mut check = Check::default();
+
+                let url = match source {
+                    Ok(source) => {
+                        check.run(&source, signals, span).await?;
+
+                        Some(source.url)
+                    }
+                    Err(e) => {
+                        check.error = Some(error_message(&e));
+
+                        None
+                    }
+                };
+
+                checks.push(check.into_value(name, url, span));
+            }
+
+            Ok(checks.into_pipeline_data(span, signals.clone()))
+        })
+    }
+}
+
+impl Client for SourcesCheck {}
+
+#[derive(Default)]
+struct Check {
+    reachable: bool,
+    ready: Option<bool>,
+    latency: Option<Duration>,
+    version: Option<String>,
+    tls: Option<&'static str>,
+    cert_expiry: Option<DateTime<FixedOffset>>,
+    error: Option<String>,
+}
+
+impl Check {
+    /// Records the outcome of checking `source`.
+    ///
+    /// Only an interrupt is returned as an error, other failures are recorded
+    /// in the check.
+    async fn run(
+        &mut self,
+        source: &Source,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<(), LabeledError> {
+        let client = match source
+            .client_builder()
+            .and_then(|builder| build(builder.tls_info(true)))
+        {
+            Ok(client) => client,
+            Err(e) => {
+                self.error = Some(error_message(&e));
+                return Ok(());
+            }
+        };
+
+        let url = match source.request_url() {
+            Ok(url) => url,
+            Err(e) => {
+                self.error = Some(error_message(&e));
+                return Ok(());
+            }
+        };
+
+        let client = match prometheus_http_query::Client::from(client, &url) {
+            Ok(client) => client,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        let https = client.base_url().scheme() == "https";
+        let insecure = source.options.insecure_skip_verify;
+        let ready_url = api_url(&client, "-/ready");
+
+        let start = Instant::now();
+        let response =
+            run_with_signal(signals, span, client.inner().get(ready_url.clone()).send()).await?;
+        let latency = start.elapsed();
+
+        match response {
+            Ok(response) => {
+                self.reachable = true;
+                self.ready = Some(response.status().is_success());
+                self.latency = Some(latency);
+                self.tls = Some(match (https, insecure) {
+                    (false, _) => "none",
+                    (true, true) => "skipped",
+                    (true, false) => "verified",
+                });
+                self.cert_expiry = cert_expiry(&response);
+            }
+            Err(e) => {
+                self.error = Some(error_chain(&e));
+
+                if https && !insecure && e.is_connect() {
+                    self.check_unverified(source, ready_url, signals, span)
+                        .await?;
+                }
+
+                return Ok(());
+            }
+        }
+
+        match run_with_signal(signals, span, client.build_information()).await? {
+            Ok(build_information) => self.version = Some(build_information.version().into()),
+            Err(e) => self.error = Some(e.to_string()),
+        }
+
+        Ok(())
+    }
+
+    /// Retries a failed connection without certificate verification to tell
+    /// TLS verification failures apart from other connection failures
+    async fn check_unverified(
+        &mut self,
+        source: &Source,
+        ready_url: reqwest::Url,
+        signals: &Signals,
+        span: Span,
+    ) -> Result<(), LabeledError> {
+        let Ok(client) = source.client_builder().and_then(|builder| {
+            build(builder.tls_info(true).tls_danger_accept_invalid_certs(true))
+        }) else {
+            return Ok(());
+        };
+
+        if let Ok(response) = run_with_signal(signals, span, client.get(ready_url).send()).await? {
+            self.reachable = true;
+            self.tls = Some("failed");
+            self.cert_expiry = cert_expiry(&response);
+        }
+
+        Ok(())
+    }
+
+    fn into_value(self, name: Option<String>, url: Option<String>, span: Span) -> Value {
+        let record = record! {
+            "name" => optional(name, span, Value::string),
+            "url" => optional(url, span, Value::string),
+            "reachable" => Value::bool(self.reachable, span),
+            "ready" => optional(self.ready, span, Value::bool),
+            "latency" => optional(self.latency, span, |latency, span| {
+                Value::duration(latency.as_nanos() as i64, span)
+            }),
+            "version" => optional(self.version, span, Value::string),
+            "tls" => optional(self.tls, span, Value::string),
+            "cert_expiry" => optional(self.cert_expiry, span, Value::date),
+            "error" => optional(self.error, span, Value::string),
+        };
+
+        Value::record(record, span)
+    }
+}
+
+fn build(builder: reqwest::ClientBuilder) -> Result<reqwest::Client, LabeledError> {
+    builder.build().map_err(|e| {
+        LabeledError::new("Unable to build prometheus client").with_help(e.to_string())
+    })
+}
+
+fn cert_expiry(response: &reqwest::Response) -> Option<DateTime<FixedOffset>> {
+    response
+        .extensions()
+        .get::<TlsInfo>()
+        .and_then(|tls_info| tls_info.peer_certificate())
+        .and_then(not_after)
+}
+
+/// Returns the end of the validity period of a DER encoded X.509 certificate
+fn not_after(der: &[u8]) -> Option<DateTime<FixedOffset>> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(der).ok()?;
+
+    DateTime::from_timestamp(certificate.validity().not_after.timestamp(), 0)
+        .map(|not_after| not_after.fixed_offset())
+}
+
+/// reqwest errors hide the underlying cause, such as a certificate
+/// verification failure, in their source
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+
+        source = error.source();
+    }
+
+    message
+}
+
+fn optional<T>(value: Option<T>, span: Span, f: impl FnOnce(T, Span) -> Value) -> Value {
+    match value {
+        Some(value) => f(value, span),
+        None => Value::nothing(span),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Check, SourcesCheck};
+    use nu_protocol::{LabeledError, Signals, Span};
+    use std::{fs, path::Path, time::Duration};
+
+    fn fixture(name: &str) -> pem::Pem {
+        let path = Path::new(file!())
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("test/fixtures")
+            .join(name);
+
+        pem::parse(fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn not_after() {
+        let der = fixture("cert.pem").into_contents();
+
+        assert_eq!(
+            "2044-05-19T05:20:32+00:00",
+            super::not_after(&der).unwrap().to_rfc3339()
+        );
+    }
+
+    #[test]
+    fn not_after_invalid() {
+        assert_eq!(None, super::not_after(fixture("key.pem").contents()));
+    }
+
+    #[test]
+    fn into_value() {
+        let check = Check {
+            reachable: true,
+            ready: Some(true),
+            latency: Some(Duration::from_millis(5)),
+            version: Some("3.0.0".into()),
+            tls: Some("verified"),
+            cert_expiry: None,
+            error: None,
+        };
+
+        let value = check.into_value(
+            Some("test".into()),
+            Some("https://prometheus.example/".into()),
+            Span::unknown(),
+        );
+        let record = value.as_record().unwrap();
+
+        assert_eq!("test", record.get("name").unwrap().as_str().unwrap());
+        assert!(record.get("reachable").unwrap().as_bool().unwrap());
+        assert_eq!(
+            5_000_000,
+            record.get("latency").unwrap().as_duration().unwrap()
+        );
+        assert_eq!("3.0.0", record.get("version").unwrap().as_str().unwrap());
+        assert!(record.get("cert_expiry").unwrap().is_nothing());
+        assert!(record.get("error").unwrap().is_nothing());
+    }
+
+    #[test]
+    fn run_load_error() {
+        let sources = vec![(
+            Some("broken".into()),
+            Err(LabeledError::new("Client key not found")),
+        )];
+
+        let rows: Vec<_> = SourcesCheck::new(sources)
+            .run(&Signals::empty(), Span::test_data())
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        let record = rows[0].as_record().unwrap();
+
+        assert_eq!("broken", record.get("name").unwrap().as_str().unwrap());
+        assert!(!record.get("reachable").unwrap().as_bool().unwrap());
+        assert_eq!(
+            "Client key not found",
+            record.get("error").unwrap().as_str().unwrap()
+        );
+    }
+}
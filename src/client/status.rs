@@ -0,0 +1,257 @@
+use crate::{Client, client::ApiRequest, signals::run_with_signal, time::rfc3339_to_value};
+use nu_protocol::{LabeledError, Record, Signals, Span, Value};
+use prometheus_http_query::Client as PrometheusClient;
+use serde::Deserialize;
+
+/// A `/api/v1/status` endpoint
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusEndpoint {
+    BuildInfo,
+    RuntimeInfo,
+    Flags,
+    Config,
+}
+
+impl StatusEndpoint {
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusEndpoint::BuildInfo => "buildinfo",
+            StatusEndpoint::RuntimeInfo => "runtimeinfo",
+            StatusEndpoint::Flags => "flags",
+            StatusEndpoint::Config => "config",
+        }
+    }
+}
+
+pub struct Status {
+    client: PrometheusClient,
+    endpoint: StatusEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    yaml: String,
+}
+
+impl Status {
+    pub fn new(client: PrometheusClient, endpoint: StatusEndpoint) -> Self {
+        Self { client, endpoint }
+    }
+
+    pub fn run(self, signals: &Signals, span: Span) -> Result<Value, LabeledError> {
+        let endpoint = self.endpoint;
+        let request = ApiRequest::new(
+            self.client.clone(),
+            format!("api/v1/status/{}", endpoint.name()),
+        );
+
+        self.runtime()?.block_on(async {
+            match endpoint {
+                StatusEndpoint::Config => {
+                    let config: Config =
+                        run_with_signal(signals, span, request.get(span)).await??;
+
+                    config_to_value(&config.yaml, span)
+                }
+                StatusEndpoint::RuntimeInfo => {
+                    let info: serde_json::Value =
+                        run_with_signal(signals, span, request.get(span)).await??;
+
+                    Ok(runtime_info_to_value(info, span))
+                }
+                StatusEndpoint::BuildInfo | StatusEndpoint::Flags => {
+                    let info: serde_json::Value =
+                        run_with_signal(signals, span, request.get(span)).await??;
+
+                    Ok(json_to_value(info, span))
+                }
+            }
+        })
+    }
+}
+
+impl Client for Status {}
+
+/// Converts runtime information with its times as datetimes
+fn runtime_info_to_value(info: serde_json::Value, span: Span) -> Value {
+    let mut value = json_to_value(info, span);
+
+    if let Value::Record { val, .. } = &mut value {
+        for name in ["startTime", "lastConfigTime"] {
+            if let Some(time) = val.to_mut().get_mut(name)
+                && let Value::String { val: string, .. } = time
+            {
+                *time = rfc3339_to_value(&string.clone(), span);
+            }
+        }
+    }
+
+    value
+}
+
+fn json_to_value(json: serde_json::Value, span: Span) -> Value {
+    match json {
+        serde_json::Value::Null => Value::nothing(span),
+        serde_json::Value::Bool(bool) => Value::bool(bool, span),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(int) => Value::int(int, span),
+            None => Value::float(number.as_f64().unwrap_or(f64::NAN), span),
+        },
+        serde_json::Value::String(string) => Value::string(string, span),
+        serde_json::Value::Array(values) => Value::list(
+            values
+                .into_iter()
+                .map(|value| json_to_value(value, span))
+                .collect(),
+            span,
+        ),
+        serde_json::Value::Object(object) => {
+            let mut record = Record::new();
+
+            for (name, value) in object {
+                record.push(name, json_to_value(value, span));
+            }
+
+            Value::record(record, span)
+        }
+    }
+}
+
+/// Parses the YAML configuration returned by the config endpoint
+fn config_to_value(yaml: &str, span: Span) -> Result<Value, LabeledError> {
+    let config: serde_yaml_ng::Value = serde_yaml_ng::from_str(yaml).map_err(|e| {
+        LabeledError::new("Invalid configuration")
+            .with_label(format!("unable to parse YAML: {e}"), span)
+    })?;
+
+    Ok(yaml_to_value(config, span))
+}
+
+fn yaml_to_value(yaml: serde_yaml_ng::Value, span: Span) -> Value {
+    use serde_yaml_ng::Value as Yaml;
+
+    match yaml {
+        Yaml::Null => Value::nothing(span),
+        Yaml::Bool(bool) => Value::bool(bool, span),
+        Yaml::Number(number) => match number.as_i64() {
+            Some(int) => Value::int(int, span),
+            None => Value::float(number.as_f64().unwrap_or(f64::NAN), span),
+        },
+        Yaml::String(string) => Value::string(string, span),
+        Yaml::Sequence(values) => Value::list(
+            values
+                .into_iter()
+                .map(|value| yaml_to_value(value, span))
+                .collect(),
+            span,
+        ),
+        Yaml::Mapping(mapping) => {
+            let mut record = Record::new();
+
+            for (key, value) in mapping {
+                let key = match key {
+                    Yaml::String(key) => key,
+                    key => serde_yaml_ng::to_string(&key)
+                        .map(|key| key.trim_end().to_string())
+                        .unwrap_or_default(),
+                };
+
+                record.push(key, yaml_to_value(value, span));
+            }
+
+            Value::record(record, span)
+        }
+        Yaml::Tagged(tagged) => yaml_to_value(tagged.value, span),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nu_protocol::{Span, Value, record};
+
+    #[test]
+    fn config_to_value() {
+        let yaml = "global:\n  scrape_interval: 15s\nscrape_configs:\n- job_name: node\n  honor_labels: true\n  static_configs:\n  - targets:\n    - localhost:9100\n";
+
+        let value = super::config_to_value(yaml, Span::test_data()).unwrap();
+
+        let expected = Value::test_record(record! {
+            "global" => Value::test_record(record! {
+                "scrape_interval" => Value::test_string("15s"),
+            }),
+            "scrape_configs" => Value::test_list(vec![Value::test_record(record! {
+                "job_name" => Value::test_string("node"),
+                "honor_labels" => Value::test_bool(true),
+                "static_configs" => Value::test_list(vec![Value::test_record(record! {
+                    "targets" => Value::test_list(vec![Value::test_string("localhost:9100")]),
+                })]),
+            })]),
+        });
+
+        assert_eq!(expected, value);
+    }
+
+    #[test]
+    fn config_to_value_invalid() {
+        let err = super::config_to_value("global: [", Span::test_data()).unwrap_err();
+
+        assert_eq!("Invalid configuration", err.msg);
+    }
+
+    #[test]
+    fn json_to_value() {
+        let json = serde_json::json!({
+            "version": "2.52.0",
+            "storage.tsdb.retention.time": "15d",
+            "goroutineCount": 48,
+            "ratio": 0.5,
+            "reloadConfigSuccess": true,
+            "GODEBUG": null,
+        });
+
+        let value = super::json_to_value(json, Span::test_data());
+
+        let expected = Value::test_record(record! {
+            "version" => Value::test_string("2.52.0"),
+            "storage.tsdb.retention.time" => Value::test_string("15d"),
+            "goroutineCount" => Value::test_int(48),
+            "ratio" => Value::test_float(0.5),
+            "reloadConfigSuccess" => Value::test_bool(true),
+            "GODEBUG" => Value::test_nothing(),
+        });
+
+        assert_eq!(expected, value);
+    }
+
+    #[test]
+    fn runtime_info_to_value() {
+        let json = serde_json::json!({
+            "startTime": "2024-05-19T05:20:32.5Z",
+            "CWD": "/prometheus",
+            "lastConfigTime": "2024-05-19T05:20:33Z",
+        });
+
+        let value = super::runtime_info_to_value(json, Span::test_data());
+
+        assert_eq!(
+            "2024-05-19T05:20:32.500+00:00",
+            value
+                .get_data_by_key("startTime")
+                .unwrap()
+                .as_date()
+                .unwrap()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+        );
+        assert!(
+            value
+                .get_data_by_key("lastConfigTime")
+                .unwrap()
+                .as_date()
+                .is_ok()
+        );
+        assert_eq!(
+            "/prometheus",
+            value.get_data_by_key("CWD").unwrap().as_str().unwrap()
+        );
+    }
+}
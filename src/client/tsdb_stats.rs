@@ -0,0 +1,173 @@
+use crate::{Client, client::ApiRequest, signals::run_with_signal};
+use chrono::DateTime;
+use nu_protocol::{LabeledError, Signals, Span, Value, record};
+use serde::Deserialize;
+
+pub struct TsdbStats {
+    request: ApiRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    head_stats: HeadStats,
+    #[serde(default)]
+    series_count_by_metric_name: Vec<Stat>,
+    #[serde(default)]
+    label_value_count_by_label_name: Vec<Stat>,
+    #[serde(default)]
+    memory_in_bytes_by_label_name: Vec<Stat>,
+    #[serde(default)]
+    series_count_by_label_value_pair: Vec<Stat>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeadStats {
+    num_series: i64,
+    num_label_pairs: i64,
+    chunk_count: i64,
+    /// Unix milliseconds
+    min_time: i64,
+    /// Unix milliseconds
+    max_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Stat {
+    name: String,
+    value: i64,
+}
+
+impl TsdbStats {
+    pub fn new(request: ApiRequest) -> Self {
+        Self { request }
+    }
+
+    pub fn run(self, signals: &Signals, span: Span) -> Result<Value, LabeledError> {
+        let Self { ref request } = self;
+
+        self.runtime()?.block_on(async {
+            let stats: Stats = run_with_signal(signals, span, request.clone().get(span)).await??;
+
+            Ok(stats_to_value(stats, span))
+        })
+    }
+}
+
+impl Client for TsdbStats {}
+
+fn stats_to_value(stats: Stats, span: Span) -> Value {
+    let head = stats.head_stats;
+
+    let time = |millis: i64| match DateTime::from_timestamp_millis(millis) {
+        Some(time) => Value::date(time.fixed_offset(), span),
+        None => Value::int(millis, span),
+    };
+
+    let head_stats = record! {
+        "num_series" => Value::int(head.num_series, span),
+        "num_label_pairs" => Value::int(head.num_label_pairs, span),
+        "chunk_count" => Value::int(head.chunk_count, span),
+        "min_time" => time(head.min_time),
+        "max_time" => time(head.max_time),
+    };
+
+    let count = |value: i64| Value::int(value, span);
+    let bytes = |value: i64| Value::filesize(value, span);
+
+    Value::record(
+        record! {
+            "head_stats" => Value::record(head_stats, span),
+            "series_count_by_metric_name" => table(stats.series_count_by_metric_name, "series", count, span),
+            "label_value_count_by_label_name" => table(stats.label_value_count_by_label_name, "values", count, span),
+            "memory_in_bytes_by_label_name" => table(stats.memory_in_bytes_by_label_name, "memory", bytes, span),
+            "series_count_by_label_value_pair" => table(stats.series_count_by_label_value_pair, "series", count, span),
+        },
+        span,
+    )
+}
+
+/// Converts a top-N list to a table with a `name` and a `column` for the value
+fn table(stats: Vec<Stat>, column: &str, value: impl Fn(i64) -> Value, span: Span) -> Value {
+    let rows = stats
+        .into_iter()
+        .map(|stat| {
+            Value::record(
+                record! {
+                    "name" => Value::string(stat.name, span),
+                    column => value(stat.value),
+                },
+                span,
+            )
+        })
+        .collect();
+
+    Value::list(rows, span)
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stats;
+    use nu_protocol::{Span, Value, record};
+
+    #[test]
+    fn stats_to_value() {
+        let data = r#"{
+          "headStats": {
+            "numSeries": 508,
+            "chunkCount": 937,
+            "minTime": 1591516800000,
+            "maxTime": 1598896800143,
+            "numLabelPairs": 1234
+          },
+          "seriesCountByMetricName": [{"name": "net_conntrack_dialer_conn_failed_total", "value": 20}],
+          "labelValueCountByLabelName": [{"name": "__name__", "value": 211}],
+          "memoryInBytesByLabelName": [{"name": "__name__", "value": 8266}],
+          "seriesCountByLabelValuePair": [{"name": "job=prometheus", "value": 425}]
+        }"#;
+
+        let stats: Stats = serde_json::from_str(data).unwrap();
+
+        let value = super::stats_to_value(stats, Span::test_data());
+
+        let head_stats = value.get_data_by_key("head_stats").unwrap();
+
+        assert_eq!(
+            508,
+            head_stats
+                .get_data_by_key("num_series")
+                .unwrap()
+                .as_int()
+                .unwrap()
+        );
+        assert_eq!(
+            1591516800000,
+            head_stats
+                .get_data_by_key("min_time")
+                .unwrap()
+                .as_date()
+                .unwrap()
+                .timestamp_millis()
+        );
+
+        assert_eq!(
+            Value::test_list(vec![Value::test_record(record! {
+                "name" => Value::test_string("net_conntrack_dialer_conn_failed_total"),
+                "series" => Value::test_int(20),
+            })]),
+            value
+                .get_data_by_key("series_count_by_metric_name")
+                .unwrap()
+        );
+        assert_eq!(
+            Value::test_list(vec![Value::test_record(record! {
+                "name" => Value::test_string("__name__"),
+                "memory" => Value::test_filesize(8266),
+            })]),
+            value
+                .get_data_by_key("memory_in_bytes_by_label_name")
+                .unwrap()
+        );
+    }
+}
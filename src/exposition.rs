@@ -0,0 +1,932 @@
+use chrono::{DateTime, FixedOffset};
+use nu_protocol::{LabeledError, Record, Span, Value};
+
+/// Text exposition formats
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Prometheus,
+    Openmetrics,
+}
+
+impl Format {
+    /// The TYPE of a metric in this format.
+    ///
+    /// OpenMetrics types without a Prometheus equivalent are untyped in the
+    /// Prometheus format.
+    fn type_name(self, kind: &str) -> &str {
+        match (self, kind) {
+            (Format::Prometheus, "counter" | "gauge" | "histogram" | "summary") => kind,
+            (Format::Prometheus, _) => "untyped",
+            (Format::Openmetrics, "untyped") => "unknown",
+            (Format::Openmetrics, _) => kind,
+        }
+    }
+
+    fn escape_help(self, help: &str) -> String {
+        let help = help.replace('\\', r"\\").replace('\n', r"\n");
+
+        match self {
+            Format::Prometheus => help,
+            Format::Openmetrics => help.replace('"', r#"\""#),
+        }
+    }
+
+    fn format_timestamp(self, timestamp: &DateTime<FixedOffset>) -> String {
+        let millis = timestamp.timestamp_millis();
+
+        match self {
+            Format::Prometheus => millis.to_string(),
+            Format::Openmetrics => {
+                let seconds = millis.div_euclid(1000);
+
+                match millis.rem_euclid(1000) {
+                    0 => seconds.to_string(),
+                    millis => format!("{seconds}.{millis:03}")
+                        .trim_end_matches('0')
+                        .to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Samples from input rows grouped by metric family
+#[derive(Debug, PartialEq)]
+struct Family {
+    name: String,
+    metadata: Metadata,
+    samples: Vec<Sample>,
+}
+
+impl Family {
+    /// The metric name of HELP, TYPE, and UNIT lines.
+    ///
+    /// OpenMetrics counter families omit the `_total` suffix of their samples
+    /// while the Prometheus format includes it.
+    fn metadata_name(&self, format: Format) -> &str {
+        let name = self.name.as_str();
+
+        if self.metadata.kind.as_deref() != Some("counter") {
+            return name;
+        }
+
+        match format {
+            Format::Prometheus => self
+                .samples
+                .first()
+                .map(|sample| sample.name.as_str())
+                .filter(|sample| sample.strip_prefix(name) == Some("_total"))
+                .unwrap_or(name),
+            Format::Openmetrics => name
+                .strip_suffix("_total")
+                .filter(|name| !name.is_empty())
+                .unwrap_or(name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Metadata {
+    kind: Option<String>,
+    help: Option<String>,
+    unit: Option<String>,
+}
+
+impl Metadata {
+    /// Merges metadata from a row, which must agree with earlier rows
+    fn merge(&mut self, other: Metadata, name: &str, span: Span) -> Result<(), LabeledError> {
+        merge(&mut self.kind, other.kind, "type", name, span)?;
+        merge(&mut self.help, other.help, "help", name, span)?;
+        merge(&mut self.unit, other.unit, "unit", name, span)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Sample {
+    name: String,
+    labels: Vec<(String, String)>,
+    value: f64,
+    timestamp: Option<DateTime<FixedOffset>>,
+    exemplar: Option<Exemplar>,
+}
+
+/// An exemplar, written only in the OpenMetrics format
+#[derive(Debug, PartialEq)]
+struct Exemplar {
+    labels: Vec<(String, String)>,
+    value: f64,
+    timestamp: Option<DateTime<FixedOffset>>,
+}
+
+/// A sample from the input along with its family, if known
+struct Row {
+    family: Option<String>,
+    metadata: Metadata,
+    sample: Sample,
+    span: Span,
+}
+
+const TYPES: [&str; 9] = [
+    "counter",
+    "gauge",
+    "gaugehistogram",
+    "histogram",
+    "info",
+    "stateset",
+    "summary",
+    "unknown",
+    "untyped",
+];
+
+/// Sample columns that are not labels.
+///
+/// Labels with these names must be placed in the `labels` record.
+pub const SAMPLE_COLUMNS: [&str; 8] = [
+    "name",
+    "value",
+    "labels",
+    "type",
+    "help",
+    "unit",
+    "timestamp",
+    "exemplar",
+];
+
+/// Serializes a table of samples in a text exposition format.
+///
+/// The input is either the output of `prometheus parse` or a table of samples.
+/// Each sample has a `name`, `value`, and optional `labels` record, `type`,
+/// `help`, `unit`, `timestamp`, and `exemplar`.  Any other columns are labels.
+/// Labels named like one of these columns must be in the `labels` record.
+///
+/// Samples are grouped into families in the order each family first appears.
+/// Samples like `_bucket`, `_count`, and `_sum` join the family of a histogram
+/// or summary.
+pub fn to_text(input: &Value, format: Format) -> Result<String, LabeledError> {
+    let families = families(rows(input)?)?;
+
+    let mut text = String::new();
+
+    for family in families {
+        let name = family.metadata_name(format);
+
+        if let Some(help) = &family.metadata.help {
+            text.push_str(&format!("# HELP {name} {}\n", format.escape_help(help)));
+        }
+
+        if let Some(kind) = &family.metadata.kind {
+            text.push_str(&format!("# TYPE {name} {}\n", format.type_name(kind)));
+        }
+
+        if let Some(unit) = &family.metadata.unit
+            && format == Format::Openmetrics
+        {
+            text.push_str(&format!("# UNIT {name} {unit}\n"));
+        }
+
+        for sample in &family.samples {
+            text.push_str(&sample.name);
+
+            if !sample.labels.is_empty() {
+                text.push_str(&format_labels(&sample.labels));
+            }
+
+            text.push(' ');
+            text.push_str(&format_value(sample.value));
+
+            if let Some(timestamp) = &sample.timestamp {
+                text.push(' ');
+                text.push_str(&format.format_timestamp(timestamp));
+            }
+
+            if let Some(exemplar) = &sample.exemplar
+                && format == Format::Openmetrics
+            {
+                text.push_str(" # ");
+                text.push_str(&format_labels(&exemplar.labels));
+                text.push(' ');
+                text.push_str(&format_value(exemplar.value));
+
+                if let Some(timestamp) = &exemplar.timestamp {
+                    text.push(' ');
+                    text.push_str(&format.format_timestamp(timestamp));
+                }
+            }
+
+            text.push('\n');
+        }
+    }
+
+    if format == Format::Openmetrics {
+        text.push_str("# EOF\n");
+    }
+
+    Ok(text)
+}
+
+fn rows(input: &Value) -> Result<Vec<Row>, LabeledError> {
+    let values = input.as_list().map_err(|_| {
+        LabeledError::new("Invalid input type")
+            .with_label("must be a table of samples", input.span())
+    })?;
+
+    let mut rows = vec![];
+
+    for value in values {
+        let record = sample_record(value)?;
+
+        match record.get("samples") {
+            Some(samples) => family_rows(record, samples, value.span(), &mut rows)?,
+            None => rows.push(row(record, value.span())?),
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Rows for a family from `prometheus parse`
+fn family_rows(
+    record: &Record,
+    samples: &Value,
+    span: Span,
+    rows: &mut Vec<Row>,
+) -> Result<(), LabeledError> {
+    let mut family = None;
+    let mut metadata = Metadata::default();
+
+    let descriptors = match record.get("descriptors") {
+        None | Some(Value::Nothing { .. }) => &[],
+        Some(descriptors) => descriptors.as_list().map_err(|_| {
+            LabeledError::new("Invalid family")
+                .with_label("descriptors must be a list", descriptors.span())
+        })?,
+    };
+
+    for descriptor in descriptors {
+        let span = descriptor.span();
+        let descriptor = sample_record(descriptor)?;
+
+        let metric = string_column(descriptor, "metric", span)?
+            .ok_or_else(|| missing_column("metric", span))?;
+
+        let column = string_column(descriptor, "descriptor", span)?
+            .ok_or_else(|| missing_column("descriptor", span))?;
+
+        let mut descriptor_metadata = Metadata::default();
+
+        match column.as_str() {
+            "type" => descriptor_metadata.kind = kind(descriptor, span)?,
+            "help" => descriptor_metadata.help = string_column(descriptor, "help", span)?,
+            "unit" => descriptor_metadata.unit = string_column(descriptor, "unit", span)?,
+            _ => {
+                return Err(LabeledError::new("Invalid descriptor")
+                    .with_label(format!("{column:?} must be type, help, or unit"), span));
+            }
+        }
+
+        merge(&mut family, Some(metric), "metric", "family", span)?;
+        metadata.merge(
+            descriptor_metadata,
+            family.as_deref().unwrap_or_default(),
+            span,
+        )?;
+    }
+
+    let samples = samples.as_list().map_err(|_| {
+        LabeledError::new("Invalid family").with_label("samples must be a list", span)
+    })?;
+
+    for sample in samples {
+        let record = sample_record(sample)?;
+
+        // The metadata of a parsed family is in its descriptors, so these
+        // columns can only be labels
+        if let Some(column) = ["type", "help", "unit"]
+            .into_iter()
+            .find(|column| record.contains(column))
+        {
+            return Err(LabeledError::new("Invalid sample")
+                .with_label(format!("{column} is not a label column"), sample.span())
+                .with_help(format!("Place a label named {column} in the labels record")));
+        }
+
+        let mut row = row(record, sample.span())?;
+
+        if family.is_some() {
+            row.family = family.clone();
+        }
+
+        let name = row
+            .family
+            .as_deref()
+            .unwrap_or(&row.sample.name)
+            .to_string();
+
+        row.metadata.merge(metadata.clone(), &name, row.span)?;
+
+        rows.push(row);
+    }
+
+    Ok(())
+}
+
+fn row(record: &Record, span: Span) -> Result<Row, LabeledError> {
+    let name = string_column(record, "name", span)?.ok_or_else(|| missing_column("name", span))?;
+
+    if !is_metric_name(&name) {
+        return Err(LabeledError::new("Invalid metric name")
+            .with_label(format!("{name:?} is not a valid metric name"), span));
+    }
+
+    let metadata = Metadata {
+        kind: kind(record, span)?,
+        help: string_column(record, "help", span)?,
+        unit: string_column(record, "unit", span)?,
+    };
+
+    let sample = Sample {
+        name,
+        labels: labels(record)?,
+        value: value(record, span)?,
+        timestamp: timestamp(record)?,
+        exemplar: exemplar(record)?,
+    };
+
+    Ok(Row {
+        family: None,
+        metadata,
+        sample,
+        span,
+    })
+}
+
+/// Groups rows into families.
+///
+/// Rows without a family join a typed family if their name is the family
+/// name with one of the sample suffixes of its type.
+fn families(rows: Vec<Row>) -> Result<Vec<Family>, LabeledError> {
+    let mut kinds: Vec<(String, String)> = vec![];
+
+    for row in &rows {
+        if let Some(kind) = &row.metadata.kind {
+            let name = match &row.family {
+                Some(family) => family.clone(),
+                None => family_name(&row.sample.name, kind).to_string(),
+            };
+
+            if !kinds.iter().any(|(family, _)| *family == name) {
+                kinds.push((name, kind.clone()));
+            }
+        }
+    }
+
+    let mut families: Vec<Family> = vec![];
+
+    for row in rows {
+        let name = match row.family {
+            Some(family) => family,
+            None => kinds
+                .iter()
+                .find(|(family, kind)| is_member(&row.sample.name, family, kind))
+                .map(|(family, _)| family.clone())
+                .unwrap_or_else(|| row.sample.name.clone()),
+        };
+
+        let family = match families.iter().position(|family| family.name == name) {
+            Some(index) => &mut families[index],
+            None => {
+                families.push(Family {
+                    name,
+                    metadata: Metadata::default(),
+                    samples: vec![],
+                });
+
+                families.last_mut().unwrap()
+            }
+        };
+
+        let Family {
+            name,
+            metadata,
+            samples,
+        } = family;
+
+        metadata.merge(row.metadata, name, row.span)?;
+        samples.push(row.sample);
+    }
+
+    Ok(families)
+}
+
+/// Sample name suffixes of a metric type
+fn suffixes(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "counter" => &["_total", "_created"],
+        "gaugehistogram" => &["_bucket", "_gcount", "_gsum"],
+        "histogram" => &["_bucket", "_count", "_sum", "_created"],
+        "info" => &["_info"],
+        "summary" => &["_count", "_sum", "_created"],
+        _ => &[],
+    }
+}
+
+/// The family name of a typed sample `name`
+fn family_name<'a>(name: &'a str, kind: &str) -> &'a str {
+    suffixes(kind)
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .filter(|name| !name.is_empty())
+        .unwrap_or(name)
+}
+
+/// Whether the sample `name` belongs to `family` of type `kind`
+fn is_member(name: &str, family: &str, kind: &str) -> bool {
+    name == family
+        || name
+            .strip_prefix(family)
+            .is_some_and(|suffix| suffixes(kind).contains(&suffix))
+}
+
+/// Sets `existing` metadata from a row, which must agree with earlier rows
+fn merge(
+    existing: &mut Option<String>,
+    new: Option<String>,
+    column: &str,
+    name: &str,
+    span: Span,
+) -> Result<(), LabeledError> {
+    match (&existing, new) {
+        (_, None) => Ok(()),
+        (None, new) => {
+            *existing = new;
+
+            Ok(())
+        }
+        (Some(existing), Some(new)) if *existing == new => Ok(()),
+        (Some(existing), Some(new)) => Err(LabeledError::new("Conflicting metric metadata")
+            .with_label(
+                format!("{column} {new:?} differs from {existing:?} for {name}"),
+                span,
+            )),
+    }
+}
+
+fn sample_record(value: &Value) -> Result<&Record, LabeledError> {
+    value.as_record().map_err(|_| {
+        LabeledError::new("Invalid sample").with_label("must be a record", value.span())
+    })
+}
+
+fn kind(record: &Record, span: Span) -> Result<Option<String>, LabeledError> {
+    let Some(kind) = string_column(record, "type", span)? else {
+        return Ok(None);
+    };
+
+    let kind = kind.to_lowercase();
+
+    if !TYPES.contains(&kind.as_str()) {
+        return Err(LabeledError::new("Invalid metric type")
+            .with_label(
+                format!("{kind:?} must be one of {}", TYPES.join(", ")),
+                span,
+            )
+            .with_help("Place a label named type in the labels record"));
+    }
+
+    Ok(Some(kind))
+}
+
+/// Labels from the `labels` record followed by any other columns
+fn labels(record: &Record) -> Result<Vec<(String, String)>, LabeledError> {
+    let nested = match record.get("labels") {
+        None | Some(Value::Nothing { .. }) => None,
+        Some(labels) => Some(labels.as_record().map_err(|_| {
+            LabeledError::new("Invalid sample").with_label("labels must be a record", labels.span())
+        })?),
+    };
+
+    let columns = record
+        .iter()
+        .filter(|(column, _)| !SAMPLE_COLUMNS.contains(&column.as_str()));
+
+    nested
+        .into_iter()
+        .flat_map(|labels| labels.iter())
+        .chain(columns)
+        .filter(|(_, value)| !value.is_nothing())
+        .map(|(name, value)| {
+            if !is_label_name(name) {
+                return Err(LabeledError::new("Invalid label name")
+                    .with_label(format!("{name:?} is not a valid label name"), value.span()));
+            }
+
+            let value = value.coerce_string().map_err(|_| {
+                LabeledError::new("Invalid label value")
+                    .with_label(format!("label {name} must be a string"), value.span())
+            })?;
+
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+fn value(record: &Record, span: Span) -> Result<f64, LabeledError> {
+    let value = record
+        .get("value")
+        .ok_or_else(|| missing_column("value", span))?;
+
+    match value {
+        Value::Int { val, .. } => Ok(*val as f64),
+        Value::Float { val, .. } => Ok(*val),
+        Value::Bool { val, .. } => Ok(if *val { 1.0 } else { 0.0 }),
+        _ => {
+            Err(LabeledError::new("Invalid sample value")
+                .with_label("must be a number", value.span()))
+        }
+    }
+}
+
+fn exemplar(record: &Record) -> Result<Option<Exemplar>, LabeledError> {
+    let exemplar = match record.get("exemplar") {
+        None | Some(Value::Nothing { .. }) => return Ok(None),
+        Some(exemplar) => exemplar,
+    };
+
+    let span = exemplar.span();
+
+    let exemplar = exemplar.as_record().map_err(|_| {
+        LabeledError::new("Invalid sample").with_label("exemplar must be a record", span)
+    })?;
+
+    Ok(Some(Exemplar {
+        labels: labels(exemplar)?,
+        value: value(exemplar, span)?,
+        timestamp: timestamp(exemplar)?,
+    }))
+}
+
+fn timestamp(record: &Record) -> Result<Option<DateTime<FixedOffset>>, LabeledError> {
+    match record.get("timestamp") {
+        None | Some(Value::Nothing { .. }) => Ok(None),
+        Some(Value::Date { val, .. }) => Ok(Some(*val)),
+        Some(value) => Err(LabeledError::new("Invalid sample timestamp")
+            .with_label("must be a datetime", value.span())),
+    }
+}
+
+fn string_column(
+    record: &Record,
+    column: &str,
+    span: Span,
+) -> Result<Option<String>, LabeledError> {
+    match record.get(column) {
+        None | Some(Value::Nothing { .. }) => Ok(None),
+        Some(Value::String { val, .. }) => Ok(Some(val.clone())),
+        Some(value) => Err(LabeledError::new("Invalid sample")
+            .with_label(format!("{column} must be a string"), value.span())
+            .with_label("in this sample", span)),
+    }
+}
+
+fn missing_column(column: &str, span: Span) -> LabeledError {
+    LabeledError::new("Invalid sample").with_label(format!("missing {column} column"), span)
+}
+
+/// Whether `name` matches `[a-zA-Z_:][a-zA-Z0-9_:]*`
+fn is_metric_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
+
+/// Whether `name` matches `[a-zA-Z_][a-zA-Z0-9_]*`
+fn is_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn format_labels(labels: &[(String, String)]) -> String {
+    let labels: Vec<_> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect();
+
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Escapes a label value for a quoted label in a selector or exposition
+pub fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value == f64::INFINITY {
+        "+Inf".into()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".into()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Format;
+    use chrono::DateTime;
+    use nu_protocol::{Value, record};
+    use rstest::rstest;
+
+    fn sample(name: &str, labels: Value, value: Value) -> Value {
+        Value::test_record(record! {
+            "name" => Value::test_string(name),
+            "labels" => labels,
+            "value" => value,
+        })
+    }
+
+    #[test]
+    fn to_text() {
+        let input = Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("job_duration_seconds"),
+                "value" => Value::test_float(12.5),
+                "type" => Value::test_string("gauge"),
+                "help" => Value::test_string("Duration of the\nlast run"),
+            }),
+            sample(
+                "job_processed_total",
+                Value::test_record(record! {
+                    "queue" => Value::test_string(r#"a "b" \c"#),
+                }),
+                Value::test_int(3),
+            ),
+            sample(
+                "job_duration_seconds",
+                Value::test_record(record! { "step" => Value::test_string("load") }),
+                Value::test_float(f64::INFINITY),
+            ),
+        ]);
+
+        let expected = r#"# HELP job_duration_seconds Duration of the\nlast run
+# TYPE job_duration_seconds gauge
+job_duration_seconds 12.5
+job_duration_seconds{step="load"} +Inf
+job_processed_total{queue="a \"b\" \\c"} 3
+"#;
+
+        assert_eq!(
+            expected,
+            super::to_text(&input, Format::Prometheus).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Format::Prometheus,
+        r#"# TYPE request_seconds histogram
+request_seconds_bucket{le="1"} 2
+request_seconds_bucket{le="+Inf"} 3
+request_seconds_sum 2.5
+request_seconds_count 3
+# HELP http_requests_total Requests "served"
+# TYPE http_requests_total counter
+http_requests_total{code="200"} 10 1700000000000
+"#
+    )]
+    #[case(
+        Format::Openmetrics,
+        r#"# TYPE request_seconds histogram
+# UNIT request_seconds seconds
+request_seconds_bucket{le="1"} 2
+request_seconds_bucket{le="+Inf"} 3
+request_seconds_sum 2.5
+request_seconds_count 3
+# HELP http_requests Requests \"served\"
+# TYPE http_requests counter
+http_requests_total{code="200"} 10 1700000000
+# EOF
+"#
+    )]
+    fn to_text_families(#[case] format: Format, #[case] expected: &str) {
+        let input = Value::test_list(vec![
+            Value::test_record(record! {
+                "name" => Value::test_string("request_seconds_bucket"),
+                "le" => Value::test_string("1"),
+                "value" => Value::test_int(2),
+                "type" => Value::test_string("histogram"),
+                "unit" => Value::test_string("seconds"),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("http_requests_total"),
+                "code" => Value::test_string("200"),
+                "value" => Value::test_int(10),
+                "type" => Value::test_string("counter"),
+                "help" => Value::test_string(r#"Requests "served""#),
+                "timestamp" => Value::test_date(
+                    DateTime::from_timestamp(1_700_000_000, 0).unwrap().fixed_offset()
+                ),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("request_seconds_bucket"),
+                "le" => Value::test_string("+Inf"),
+                "value" => Value::test_int(3),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("request_seconds_sum"),
+                "le" => Value::test_nothing(),
+                "value" => Value::test_float(2.5),
+            }),
+            Value::test_record(record! {
+                "name" => Value::test_string("request_seconds_count"),
+                "le" => Value::test_nothing(),
+                "value" => Value::test_int(3),
+            }),
+        ]);
+
+        assert_eq!(expected, super::to_text(&input, format).unwrap());
+    }
+
+    #[test]
+    fn to_text_parsed() {
+        let descriptor = |descriptor: &str, value: &str| {
+            Value::test_record(record! {
+                "descriptor" => Value::test_string(descriptor),
+                "metric" => Value::test_string("up"),
+                descriptor => Value::test_string(value),
+            })
+        };
+
+        let input = Value::test_list(vec![Value::test_record(record! {
+            "descriptors" => Value::test_list(vec![
+                descriptor("type", "Gauge"),
+                descriptor("help", "Target is up"),
+            ]),
+            "samples" => Value::test_list(vec![
+                Value::test_record(record! {
+                    "name" => Value::test_string("up"),
+                    "job" => Value::test_string("node"),
+                    "value" => Value::test_float(1.0),
+                    "exemplar" => Value::test_record(record! {
+                        "labels" => Value::test_record(record! {
+                            "trace_id" => Value::test_string("abc"),
+                        }),
+                        "value" => Value::test_float(0.5),
+                    }),
+                }),
+            ]),
+        })]);
+
+        let expected = "# HELP up Target is up\n# TYPE up gauge\nup{job=\"node\"} 1 # {trace_id=\"abc\"} 0.5\n# EOF\n";
+
+        assert_eq!(
+            expected,
+            super::to_text(&input, Format::Openmetrics).unwrap()
+        );
+    }
+
+    #[test]
+    fn to_text_reserved_labels() {
+        let input = Value::test_list(vec![sample(
+            "up",
+            Value::test_record(record! {
+                "type" => Value::test_string("node"),
+                "name" => Value::test_string("a"),
+                "value" => Value::test_string("b"),
+            }),
+            Value::test_int(1),
+        )]);
+
+        assert_eq!(
+            "up{type=\"node\",name=\"a\",value=\"b\"} 1\n",
+            super::to_text(&input, Format::Prometheus).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("type")]
+    #[case("help")]
+    #[case("unit")]
+    fn to_text_parsed_reserved_label(#[case] column: &str) {
+        let input = Value::test_list(vec![Value::test_record(record! {
+            "samples" => Value::test_list(vec![
+                Value::test_record(record! {
+                    "name" => Value::test_string("up"),
+                    column => Value::test_string("counter"),
+                    "value" => Value::test_float(1.0),
+                }),
+            ]),
+        })]);
+
+        let error = super::to_text(&input, Format::Prometheus).unwrap_err();
+
+        assert_eq!(
+            format!("{column} is not a label column"),
+            error.labels[0].text
+        );
+    }
+
+    #[rstest]
+    #[case(
+        sample("0up", Value::test_nothing(), Value::test_int(1)),
+        "Invalid metric name"
+    )]
+    #[case(
+        sample(
+            "up",
+            Value::test_record(record! { "a-b" => Value::test_string("x") }),
+            Value::test_int(1),
+        ),
+        "Invalid label name"
+    )]
+    #[case(
+        sample("up", Value::test_nothing(), Value::test_string("1")),
+        "Invalid sample value"
+    )]
+    #[case(
+        Value::test_record(record! {
+            "name" => Value::test_string("up"),
+            "value" => Value::test_int(1),
+            "type" => Value::test_string("meter"),
+        }),
+        "Invalid metric type"
+    )]
+    #[case(
+        Value::test_record(record! {
+            "name" => Value::test_string("up"),
+            "value" => Value::test_int(1),
+            "timestamp" => Value::test_int(1),
+        }),
+        "Invalid sample timestamp"
+    )]
+    #[case(Value::test_record(record! { "value" => Value::test_int(1) }), "Invalid sample")]
+    fn to_text_error(#[case] row: Value, #[case] expected: &str) {
+        let input = Value::test_list(vec![row]);
+
+        assert_eq!(
+            expected,
+            super::to_text(&input, Format::Prometheus).unwrap_err().msg
+        );
+    }
+
+    #[test]
+    fn to_text_conflicting_type() {
+        let row = |kind| {
+            Value::test_record(record! {
+                "name" => Value::test_string("up"),
+                "value" => Value::test_int(1),
+                "type" => Value::test_string(kind),
+            })
+        };
+
+        let input = Value::test_list(vec![row("gauge"), row("counter")]);
+
+        assert_eq!(
+            "Conflicting metric metadata",
+            super::to_text(&input, Format::Prometheus).unwrap_err().msg
+        );
+    }
+
+    #[rstest]
+    #[case("request_seconds_bucket", "histogram", "request_seconds")]
+    #[case("request_seconds_count", "summary", "request_seconds")]
+    #[case("requests_total", "counter", "requests")]
+    #[case("queue_count", "gauge", "queue_count")]
+    #[case("_sum", "summary", "_sum")]
+    fn family_name(#[case] name: &str, #[case] kind: &str, #[case] expected: &str) {
+        assert_eq!(expected, super::family_name(name, kind));
+    }
+
+    #[rstest]
+    #[case(0, "1700000000")]
+    #[case(250, "1700000000.25")]
+    #[case(5, "1700000000.005")]
+    fn format_timestamp(#[case] millis: i64, #[case] expected: &str) {
+        let timestamp = DateTime::from_timestamp_millis(1_700_000_000_000 + millis)
+            .unwrap()
+            .fixed_offset();
+
+        assert_eq!(expected, Format::Openmetrics.format_timestamp(&timestamp));
+    }
+
+    #[rstest]
+    #[case(1.0, "1")]
+    #[case(0.25, "0.25")]
+    #[case(f64::NAN, "NaN")]
+    #[case(f64::NEG_INFINITY, "-Inf")]
+    fn format_value(#[case] value: f64, #[case] expected: &str) {
+        assert_eq!(expected, super::format_value(value));
+    }
+}
@@ -1,8 +1,10 @@
 mod client;
+mod exposition;
 mod prometheus;
 mod query;
 mod signals;
 mod source;
+mod time;
 
 use client::Client;
 use nu_plugin::{MsgPackSerializer, serve_plugin};
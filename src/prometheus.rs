@@ -1,25 +1,52 @@
+mod alertmanager_alerts_command;
+mod alertmanager_command;
+mod alertmanager_silence_command;
+mod alertmanager_silences_command;
+mod alerts_command;
 mod label_names_command;
 mod label_values_command;
 mod metric_metadata_command;
 mod parse_command;
 mod prometheus_command;
+mod push_command;
 mod query_command;
+mod query_exemplars_command;
 mod query_range_command;
+mod rules_command;
 mod scrape_command;
 mod series_command;
+mod sources_check_command;
 mod sources_command;
+mod status_command;
 mod targets_command;
+mod to_command;
+mod tsdb_command;
+mod tsdb_stats_command;
 
+use crate::client::StatusEndpoint;
+use crate::exposition::Format;
 use crate::prometheus::{
-    label_names_command::LabelNamesCommand, label_values_command::LabelValuesCommand,
-    metric_metadata_command::MetricMetadataCommand, prometheus_command::PrometheusCommand,
-    query_command::QueryCommand, query_range_command::QueryRangeCommand,
-    series_command::SeriesCommand, sources_command::SourcesCommand,
-    targets_command::TargetsCommand,
+    alerts_command::AlertsCommand, label_names_command::LabelNamesCommand,
+    label_values_command::LabelValuesCommand, metric_metadata_command::MetricMetadataCommand,
+    prometheus_command::PrometheusCommand, query_command::QueryCommand,
+    query_exemplars_command::QueryExemplarsCommand, query_range_command::QueryRangeCommand,
+    rules_command::RulesCommand, series_command::SeriesCommand,
+    sources_check_command::SourcesCheckCommand, sources_command::SourcesCommand,
+    targets_command::TargetsCommand, tsdb_command::TsdbCommand,
+    tsdb_stats_command::TsdbStatsCommand,
 };
+use alertmanager_alerts_command::AlertmanagerAlertsCommand;
+use alertmanager_command::AlertmanagerCommand;
+use alertmanager_silence_command::AlertmanagerSilenceAddCommand;
+use alertmanager_silence_command::AlertmanagerSilenceCommand;
+use alertmanager_silence_command::AlertmanagerSilenceExpireCommand;
+use alertmanager_silences_command::AlertmanagerSilencesCommand;
 use nu_plugin::Plugin;
 use parse_command::ParseCommand;
+use push_command::{PushCommand, PushDeleteCommand};
 use scrape_command::ScrapeCommand;
+use status_command::{StatusCommand, StatusEndpointCommand};
+use to_command::ToCommand;
 
 #[derive(Clone)]
 pub struct Prometheus;
@@ -27,17 +54,38 @@ pub struct Prometheus;
 impl Plugin for Prometheus {
     fn commands(&self) -> Vec<Box<dyn nu_plugin::PluginCommand<Plugin = Self>>> {
         vec![
+            Box::new(AlertmanagerAlertsCommand),
+            Box::new(AlertmanagerCommand),
+            Box::new(AlertmanagerSilenceAddCommand),
+            Box::new(AlertmanagerSilenceCommand),
+            Box::new(AlertmanagerSilenceExpireCommand),
+            Box::new(AlertmanagerSilencesCommand),
+            Box::new(AlertsCommand),
             Box::new(LabelNamesCommand),
             Box::new(LabelValuesCommand),
             Box::new(MetricMetadataCommand),
             Box::new(ParseCommand),
             Box::new(PrometheusCommand),
+            Box::new(PushCommand),
+            Box::new(PushDeleteCommand),
             Box::new(QueryCommand),
+            Box::new(QueryExemplarsCommand),
             Box::new(QueryRangeCommand),
+            Box::new(RulesCommand),
             Box::new(SeriesCommand),
             Box::new(ScrapeCommand),
+            Box::new(SourcesCheckCommand),
             Box::new(SourcesCommand),
+            Box::new(StatusCommand),
+            Box::new(StatusEndpointCommand::new(StatusEndpoint::BuildInfo)),
+            Box::new(StatusEndpointCommand::new(StatusEndpoint::Config)),
+            Box::new(StatusEndpointCommand::new(StatusEndpoint::Flags)),
+            Box::new(StatusEndpointCommand::new(StatusEndpoint::RuntimeInfo)),
             Box::new(TargetsCommand),
+            Box::new(ToCommand::new(Format::Openmetrics)),
+            Box::new(ToCommand::new(Format::Prometheus)),
+            Box::new(TsdbCommand),
+            Box::new(TsdbStatsCommand),
         ]
     }
 
@@ -0,0 +1,60 @@
+use crate::{
+    Prometheus,
+    client::Alertmanager,
+    prometheus::alertmanager_command::filter,
+    source::{Service, Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, PipelineMetadata, Signature, SyntaxShape, Type};
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerAlertsCommand;
+
+impl PluginCommand for AlertmanagerAlertsCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager alerts"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .optional(
+                "selector",
+                SyntaxShape::String,
+                "Only return alerts with labels matching this selector",
+            )
+            .switch("no-flatten", "Do not flatten labels into record", None)
+            .service_flags(Service::Alertmanager)
+            .input_output_type(Type::Nothing, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "List alerts received by the Alertmanager"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let source = Source::for_service(call, engine, Service::Alertmanager)?;
+
+        let filter = filter(call, 0)?;
+        let flatten = !call.has_flag("no-flatten")?;
+
+        Alertmanager::new(&source)?
+            .alerts(filter, flatten, engine.signals(), call_span)
+            .map(|pipeline| {
+                let metadata = PipelineMetadata::default()
+                    .with_table_width_priority_columns(call_span, ["alertname", "state"]);
+
+                pipeline.set_metadata(Some(metadata))
+            })
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    Prometheus,
+    client::{Matcher, SelectorParser},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, Span, Type, Value};
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerCommand;
+
+impl SimplePluginCommand for AlertmanagerCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).input_output_type(Type::Nothing, Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Alertmanager alerts and silences"
+    }
+
+    fn extra_description(&self) -> &str {
+        "The Alertmanager is found from the alertmanager_url of the source or --alertmanager-url.  It is requested with the TLS, authentication, and client options of the source, or of the flags when --alertmanager-url is given without --source or --url."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        _call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        eprintln!("{}", engine.get_help()?);
+
+        Ok(Value::nothing(Span::unknown()))
+    }
+}
+
+/// Parses an optional selector argument into matchers
+pub fn filter(call: &EvaluatedCall, position: usize) -> Result<Vec<Matcher>, LabeledError> {
+    call.opt::<Value>(position)?
+        .map(|selector| SelectorParser::matchers(&selector))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
@@ -0,0 +1,193 @@
+use crate::{
+    Prometheus,
+    client::{Alertmanager, SelectorParser, Silence, is_silence_id},
+    source::{Service, Source, SourceSignature},
+    time::{time_flag, time_shape},
+};
+use chrono::{TimeDelta, Utc};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, Span, SyntaxShape, Type, Value};
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerSilenceCommand;
+
+impl SimplePluginCommand for AlertmanagerSilenceCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager silence"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).input_output_type(Type::Nothing, Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Add and expire Alertmanager silences"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        _call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        eprintln!("{}", engine.get_help()?);
+
+        Ok(Value::nothing(Span::unknown()))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerSilenceAddCommand;
+
+impl SimplePluginCommand for AlertmanagerSilenceAddCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager silence add"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .required(
+                "selector",
+                SyntaxShape::String,
+                "Silence alerts with labels matching this selector",
+            )
+            .required_named(
+                "comment",
+                SyntaxShape::String,
+                "Reason for the silence",
+                Some('m'),
+            )
+            .named(
+                "duration",
+                SyntaxShape::Duration,
+                "How long the silence lasts, default 1hr",
+                Some('d'),
+            )
+            .named(
+                "start",
+                time_shape(),
+                "When the silence starts, default now",
+                None,
+            )
+            .named(
+                "creator",
+                SyntaxShape::String,
+                "Who created the silence, default $env.USER",
+                None,
+            )
+            .service_flags(Service::Alertmanager)
+            .input_output_type(Type::Nothing, Type::record())
+    }
+
+    fn description(&self) -> &str {
+        "Add an Alertmanager silence"
+    }
+
+    fn extra_description(&self) -> &str {
+        "Alertmanager alerts have no metric name, so the selector must only match labels such as alertname."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let source = Source::for_service(call, engine, Service::Alertmanager)?;
+
+        let selector: Value = call.req(0)?;
+        let matchers = SelectorParser::matchers(&selector)?;
+
+        if matchers.is_empty() {
+            return Err(LabeledError::new("Invalid silence")
+                .with_label("must match at least one label", selector.span()));
+        }
+
+        let duration = match call.get_flag_value("duration") {
+            Some(duration) => {
+                let span = duration.span();
+
+                Some(TimeDelta::nanoseconds(duration.as_duration()?))
+                    .filter(|duration| *duration > TimeDelta::zero())
+                    .ok_or_else(|| {
+                        LabeledError::new("Invalid argument")
+                            .with_label("--duration must be positive", span)
+                    })?
+            }
+            None => TimeDelta::hours(1),
+        };
+
+        let starts_at = time_flag(call, "start")?.unwrap_or_else(|| Utc::now().fixed_offset());
+
+        let created_by = match call.get_flag_value("creator") {
+            Some(creator) => creator,
+            None => engine.get_env_var("USER")?.ok_or_else(|| {
+                LabeledError::new("Silence creator not specified")
+                    .with_label("provide --creator or set $env.USER", call.head)
+            })?,
+        };
+
+        let silence = Silence {
+            matchers,
+            starts_at,
+            ends_at: starts_at + duration,
+            created_by: created_by.into_string()?,
+            comment: call.get_flag("comment")?.unwrap_or_default(),
+        };
+
+        Alertmanager::new(&source)?.add_silence(silence, engine.signals(), call.head)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerSilenceExpireCommand;
+
+impl SimplePluginCommand for AlertmanagerSilenceExpireCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager silence expire"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .required("id", SyntaxShape::String, "ID of the silence to expire")
+            .service_flags(Service::Alertmanager)
+            .input_output_type(Type::Nothing, Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Expire an Alertmanager silence"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let source = Source::for_service(call, engine, Service::Alertmanager)?;
+
+        let id: Value = call.req(0)?;
+        let span = id.span();
+        let id = id.into_string()?;
+
+        if !is_silence_id(&id) {
+            return Err(LabeledError::new("Invalid silence ID").with_label(
+                "must be a UUID like 0f5c6d3e-8e6b-4d4a-9a41-6c1f0a1d2b3c",
+                span,
+            ));
+        }
+
+        Alertmanager::new(&source)?.expire_silence(&id, engine.signals(), call.head)
+    }
+}
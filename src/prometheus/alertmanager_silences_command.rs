@@ -0,0 +1,58 @@
+use crate::{
+    Prometheus,
+    client::Alertmanager,
+    prometheus::alertmanager_command::filter,
+    source::{Service, Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, PipelineMetadata, Signature, SyntaxShape, Type};
+
+#[derive(Clone, Default)]
+pub struct AlertmanagerSilencesCommand;
+
+impl PluginCommand for AlertmanagerSilencesCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alertmanager silences"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .optional(
+                "selector",
+                SyntaxShape::String,
+                "Only return silences matching this selector",
+            )
+            .service_flags(Service::Alertmanager)
+            .input_output_type(Type::Nothing, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "List Alertmanager silences"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let source = Source::for_service(call, engine, Service::Alertmanager)?;
+
+        let filter = filter(call, 0)?;
+
+        Alertmanager::new(&source)?
+            .silences(filter, engine.signals(), call_span)
+            .map(|pipeline| {
+                let metadata = PipelineMetadata::default()
+                    .with_table_width_priority_columns(call_span, ["id", "state", "matchers"]);
+
+                pipeline.set_metadata(Some(metadata))
+            })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    Prometheus,
+    client::{Alerts, ApiRequest},
+    source::{Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, PipelineMetadata, Signature, Type};
+use prometheus_http_query::Client;
+
+#[derive(Clone, Default)]
+pub struct AlertsCommand;
+
+impl PluginCommand for AlertsCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus alerts"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .source_flags()
+            .switch("no-flatten", "Do not flatten labels into record", None)
+            .input_output_type(Type::Nothing, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "Query for firing and pending alerts"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let client: Client = Source::from(call, engine)?.try_into()?;
+
+        let flatten = !call.has_flag("no-flatten")?;
+
+        Alerts::new(ApiRequest::new(client, "api/v1/alerts"), flatten)
+            .run(engine.signals(), call_span)
+            .map(|pipeline| {
+                let metadata = PipelineMetadata::default()
+                    .with_table_width_priority_columns(call_span, ["alertname", "state"]);
+
+                pipeline.set_metadata(Some(metadata))
+            })
+    }
+}
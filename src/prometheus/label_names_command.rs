@@ -1,10 +1,11 @@
 use crate::{
     Prometheus,
     client::{LabelNames, LabelNamesBuilder},
-    source::Source,
+    source::{Source, SourceSignature},
+    time::{time_flag, time_shape},
 };
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
-use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
+use nu_protocol::{LabeledError, PipelineData, Signature, Type};
 
 #[derive(Clone, Default)]
 pub struct LabelNamesCommand;
@@ -21,28 +22,17 @@ impl PluginCommand for LabelNamesCommand {
             .description(self.description())
             .named(
                 "start",
-                SyntaxShape::DateTime,
+                time_shape(),
                 "Start timestamp for a labels query",
                 None,
             )
             .named(
                 "end",
-                SyntaxShape::DateTime,
+                time_shape(),
                 "End timestamp for a labels query",
                 None,
             )
-            .named(
-                "source",
-                SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
-            )
-            .named(
-                "url",
-                SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
-            )
+            .source_flags()
             .input_output_types(vec![
                 (Type::Nothing, Type::table()),
                 (Type::String, Type::table()),
@@ -69,8 +59,8 @@ impl PluginCommand for LabelNamesCommand {
 
         let builder = LabelNamesBuilder::new(source.try_into()?);
 
-        let start = call.get_flag("start")?;
-        let end = call.get_flag("end")?;
+        let start = time_flag(call, "start")?;
+        let end = time_flag(call, "end")?;
 
         LabelNames::new(
             builder.names(start, end, &selectors)?,
@@ -1,7 +1,8 @@
 use crate::{
     Prometheus,
     client::{LabelValues, LabelValuesBuilder},
-    source::Source,
+    source::{Source, SourceSignature},
+    time::{time_flag, time_shape},
 };
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
 use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
@@ -21,28 +22,17 @@ impl PluginCommand for LabelValuesCommand {
             .description(self.description())
             .named(
                 "start",
-                SyntaxShape::DateTime,
+                time_shape(),
                 "Start timestamp for a labels query",
                 None,
             )
             .named(
                 "end",
-                SyntaxShape::DateTime,
+                time_shape(),
                 "End timestamp for a labels query",
                 None,
             )
-            .named(
-                "source",
-                SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
-            )
-            .named(
-                "url",
-                SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
-            )
+            .source_flags()
             .rest(
                 "selectors",
                 SyntaxShape::String,
@@ -75,8 +65,8 @@ impl PluginCommand for LabelValuesCommand {
 
         let builder = LabelValuesBuilder::new(source.try_into()?);
 
-        let start = call.get_flag("start")?;
-        let end = call.get_flag("end")?;
+        let start = time_flag(call, "start")?;
+        let end = time_flag(call, "end")?;
         let selectors = call.rest(0)?;
 
         LabelValues::new(
@@ -1,4 +1,4 @@
-use crate::{Prometheus, Source, client::MetricMetadata};
+use crate::{Prometheus, Source, client::MetricMetadata, source::SourceSignature};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
 use nu_protocol::{LabeledError, PipelineData, Signature, Span, SyntaxShape, Type};
 use prometheus_http_query::Client;
@@ -16,18 +16,7 @@ impl PluginCommand for MetricMetadataCommand {
     fn signature(&self) -> nu_protocol::Signature {
         Signature::build(self.name())
             .description(self.description())
-            .named(
-                "source",
-                SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
-            )
-            .named(
-                "url",
-                SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
-            )
+            .source_flags()
             .named(
                 "limit",
                 SyntaxShape::Int,
@@ -24,7 +24,16 @@ impl SimplePluginCommand for ParseCommand {
                 "Metrics format, prometheus (default) or openmetrics",
                 None,
             )
-            .input_output_types(vec![(Type::String, Type::table())])
+            .switch("no-flatten", "Do not flatten labels into record", None)
+            .switch(
+                "lenient",
+                "Skip invalid lines and report them in an errors column",
+                None,
+            )
+            .input_output_types(vec![
+                (Type::String, Type::table()),
+                (Type::String, Type::record()),
+            ])
     }
 
     fn description(&self) -> &str {
@@ -55,6 +64,9 @@ impl SimplePluginCommand for ParseCommand {
             }
         }
 
+        parser.set_flatten(!call.has_flag("no-flatten")?);
+        parser.set_lenient(call.has_flag("lenient")?);
+
         parser.run()
     }
 }
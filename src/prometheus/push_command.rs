@@ -0,0 +1,118 @@
+use crate::{
+    Prometheus,
+    client::{Grouping, Pushgateway},
+    exposition::{self, Format},
+    source::{Service, Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, SyntaxShape, Type, Value};
+
+/// Adds the flags identifying a group of pushed metrics
+fn grouping_flags(signature: Signature) -> Signature {
+    signature
+        .required_named("job", SyntaxShape::String, "Job of the metrics", Some('j'))
+        .named(
+            "grouping",
+            SyntaxShape::Record(Default::default()),
+            "Grouping labels in addition to the job",
+            Some('g'),
+        )
+        .service_flags(Service::Pushgateway)
+}
+
+fn grouping(call: &EvaluatedCall) -> Result<Grouping, LabeledError> {
+    let job: String = call.get_flag("job")?.unwrap_or_default();
+
+    let labels = match call.get_flag_value("grouping") {
+        Some(grouping) => grouping
+            .as_record()?
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), value.coerce_string()?)))
+            .collect::<Result<_, LabeledError>>()?,
+        None => vec![],
+    };
+
+    Ok(Grouping { job, labels })
+}
+
+#[derive(Clone, Default)]
+pub struct PushCommand;
+
+impl SimplePluginCommand for PushCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus push"
+    }
+
+    fn signature(&self) -> Signature {
+        grouping_flags(
+            Signature::build(self.name())
+                .description(self.description())
+                .switch(
+                    "replace",
+                    "Replace all metrics in the group instead of those with the same names",
+                    None,
+                ),
+        )
+        .input_output_type(Type::table(), Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Push metrics to a Pushgateway"
+    }
+
+    fn extra_description(&self) -> &str {
+        "Input rows are samples as for `to prometheus`.  The Pushgateway is found from the pushgateway_url of the source or --pushgateway-url.  No Prometheus source is needed when --pushgateway-url is given without --source or --url."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let source = Source::for_service(call, engine, Service::Pushgateway)?;
+
+        let grouping = grouping(call)?;
+        let metrics = exposition::to_text(input, Format::Prometheus)?;
+        let replace = call.has_flag("replace")?;
+
+        Pushgateway::new(&source)?.push(&grouping, metrics, replace, engine.signals(), call.head)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PushDeleteCommand;
+
+impl SimplePluginCommand for PushDeleteCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus push delete"
+    }
+
+    fn signature(&self) -> Signature {
+        grouping_flags(Signature::build(self.name()).description(self.description()))
+            .input_output_type(Type::Nothing, Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Delete a group of metrics from a Pushgateway"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let source = Source::for_service(call, engine, Service::Pushgateway)?;
+
+        let grouping = grouping(call)?;
+
+        Pushgateway::new(&source)?.delete(&grouping, engine.signals(), call.head)
+    }
+}
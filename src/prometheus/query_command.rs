@@ -1,6 +1,12 @@
-use crate::{Prometheus, client::QueryBuilder, source::Source};
+use crate::{
+    Prometheus,
+    client::{QueryBuilder, ResultType},
+    query::Timestamps,
+    source::{Source, SourceSignature},
+    time::{time_flag, time_shape},
+};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
-use nu_protocol::{LabeledError, PipelineData, PipelineMetadata, Signature, SyntaxShape, Type};
+use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
 
 #[derive(Clone, Default)]
 pub struct QueryCommand;
@@ -17,24 +23,40 @@ impl PluginCommand for QueryCommand {
             .description(self.description())
             .named(
                 "at",
-                SyntaxShape::DateTime,
-                "Evaluation timestamp for an instant query",
+                time_shape(),
+                "Evaluation timestamp for an instant query, may be relative like -1hr or now-15m",
                 None,
             )
             .named("timeout", SyntaxShape::Number, "Evaluation timeout", None)
+            .source_flags()
+            .switch("no-flatten", "Do not flatten labels into record", None)
             .named(
-                "source",
+                "timestamps",
                 SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
+                "Timestamp format: datetime (default), float unix seconds, or int unix milliseconds",
+                None,
             )
             .named(
-                "url",
+                "result-type",
                 SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
+                "Fail unless the query returns this result type: vector, matrix, scalar, or string",
+                None,
+            )
+            .switch(
+                "with-warnings",
+                "Output a record with the result and any warnings and infos instead of adding them to the metadata",
+                None,
+            )
+            .switch(
+                "stats",
+                "Output a record with the result and query timings and sample counts",
+                None,
+            )
+            .switch(
+                "fail-on-warning",
+                "Fail if the query returns any warnings",
+                None,
             )
-            .switch("no-flatten", "Do not flatten labels into record", None)
             .input_output_type(Type::String, Type::Any)
     }
 
@@ -65,13 +87,35 @@ impl PluginCommand for QueryCommand {
             query_builder.flatten();
         }
 
-        let at = call.get_flag("at")?;
+        if let Some(timestamps) = call.get_flag_value("timestamps") {
+            query_builder.timestamps(Timestamps::from_value(&timestamps)?);
+        }
+
+        if let Some(result_type) = call.get_flag_value("result-type") {
+            query_builder.result_type(ResultType::from_value(&result_type)?);
+        }
+
+        if call.has_flag("with-warnings")? {
+            query_builder.with_warnings();
+        }
+
+        if call.has_flag("stats")? {
+            query_builder.stats();
+        }
+
+        if call.has_flag("fail-on-warning")? {
+            query_builder.fail_on_warning();
+        }
+
+        let at = time_flag(call, "at")?;
 
         query_builder
             .instant(at, &query, query_span, call_span)
             .run(engine.signals())
-            .map(|pipeline| {
-                let metadata = PipelineMetadata::default()
+            .map(|mut pipeline| {
+                let metadata = pipeline
+                    .take_metadata()
+                    .unwrap_or_default()
                     .with_table_width_priority_columns(call_span, ["name", "value"]);
 
                 pipeline.set_metadata(Some(metadata))
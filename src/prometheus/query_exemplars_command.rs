@@ -0,0 +1,81 @@
+use crate::{
+    Prometheus,
+    client::{ApiRequest, QueryExemplars},
+    query::Timestamps,
+    source::{Source, SourceSignature},
+    time::{time_flag, time_shape},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
+use prometheus_http_query::Client;
+
+#[derive(Clone, Default)]
+pub struct QueryExemplarsCommand;
+
+impl PluginCommand for QueryExemplarsCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus query exemplars"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .named(
+                "start",
+                time_shape(),
+                "Start timestamp for an exemplars query, may be relative like -1hr or now-15m",
+                None,
+            )
+            .named(
+                "end",
+                time_shape(),
+                "End timestamp for an exemplars query, may be relative like -1hr or now-15m",
+                None,
+            )
+            .source_flags()
+            .named(
+                "timestamps",
+                SyntaxShape::String,
+                "Timestamp format: datetime (default), float unix seconds, or int unix milliseconds",
+                None,
+            )
+            .input_output_type(Type::String, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "Query for exemplars"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Prometheus,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        query: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let (query, query_span, _) = query.collect_string_strict(call_span)?;
+
+        let client: Client = Source::from(call, engine)?.try_into()?;
+
+        let mut request = ApiRequest::new(client, "api/v1/query_exemplars").param("query", query);
+
+        if let Some(start) = time_flag(call, "start")? {
+            request = request.time("start", start);
+        }
+
+        if let Some(end) = time_flag(call, "end")? {
+            request = request.time("end", end);
+        }
+
+        let timestamps = match call.get_flag_value("timestamps") {
+            Some(timestamps) => Timestamps::from_value(&timestamps)?,
+            None => Timestamps::default(),
+        };
+
+        QueryExemplars::new(request, query_span, timestamps).run(engine.signals(), call_span)
+    }
+}
@@ -1,6 +1,13 @@
-use crate::{Prometheus, client::QueryBuilder, source::Source};
+use crate::{
+    Prometheus,
+    client::{QueryBuilder, ResultType},
+    query::Timestamps,
+    source::{Source, SourceSignature},
+    time::{DEFAULT_POINTS, auto_step, time_flag, time_shape},
+};
+use chrono::{TimeDelta, Utc};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
-use nu_protocol::{LabeledError, PipelineData, PipelineMetadata, Signature, SyntaxShape, Type};
+use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
 
 #[derive(Clone, Default)]
 pub struct QueryRangeCommand;
@@ -17,36 +24,70 @@ impl PluginCommand for QueryRangeCommand {
             .description(self.description())
             .named(
                 "start",
-                SyntaxShape::DateTime,
-                "Start timestamp for a range query",
+                time_shape(),
+                "Start timestamp for a range query, may be relative like -1hr or now-15m",
+                None,
+            )
+            .named(
+                "range",
+                SyntaxShape::Duration,
+                "Duration of the range query ending at --end, instead of --start",
                 None,
             )
             .named(
                 "end",
-                SyntaxShape::DateTime,
-                "End timestamp for a range query",
+                time_shape(),
+                "End timestamp for a range query, may be relative like -1hr or now-15m, defaults to now",
                 None,
             )
             .named(
                 "step",
                 SyntaxShape::Duration,
-                "Query resolution step width",
+                "Query resolution step width, computed from --points by default",
+                None,
+            )
+            .named(
+                "points",
+                SyntaxShape::Int,
+                "Target number of points per series when --step is not given, default 250",
                 None,
             )
             .named("timeout", SyntaxShape::Number, "Evaluation timeout", None)
             .named(
-                "source",
+                "concurrency",
+                SyntaxShape::Int,
+                "Number of chunks of a long range query to request at once, default 1",
+                None,
+            )
+            .source_flags()
+            .switch("no-flatten", "Do not flatten labels into record", None)
+            .named(
+                "timestamps",
                 SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
+                "Timestamp format: datetime (default), float unix seconds, or int unix milliseconds",
+                None,
             )
             .named(
-                "url",
+                "result-type",
                 SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
+                "Fail unless the query returns this result type: vector, matrix, scalar, or string",
+                None,
+            )
+            .switch(
+                "with-warnings",
+                "Output a record with the result and any warnings and infos instead of adding them to the metadata",
+                None,
+            )
+            .switch(
+                "stats",
+                "Output a record with the result and query timings and sample counts",
+                None,
+            )
+            .switch(
+                "fail-on-warning",
+                "Fail if the query returns any warnings",
+                None,
             )
-            .switch("no-flatten", "Do not flatten labels into record", None)
             .input_output_type(Type::String, Type::Any)
     }
 
@@ -77,35 +118,99 @@ impl PluginCommand for QueryRangeCommand {
             query_builder.flatten();
         }
 
-        let start = call.get_flag("start")?;
-        let end = call.get_flag("end")?;
-        let step = call.get_flag::<i64>("step")?;
+        if let Some(timestamps) = call.get_flag_value("timestamps") {
+            query_builder.timestamps(Timestamps::from_value(&timestamps)?);
+        }
+
+        if let Some(result_type) = call.get_flag_value("result-type") {
+            query_builder.result_type(ResultType::from_value(&result_type)?);
+        }
+
+        if call.has_flag("with-warnings")? {
+            query_builder.with_warnings();
+        }
+
+        if call.has_flag("stats")? {
+            query_builder.stats();
+        }
+
+        if call.has_flag("fail-on-warning")? {
+            query_builder.fail_on_warning();
+        }
+
+        if let Some(concurrency) = call.get_flag_value("concurrency") {
+            let span = concurrency.span();
 
-        let (Some(start), Some(end), Some(step)) = (start, end, step) else {
-            let mut missing = vec![];
+            let concurrency = usize::try_from(concurrency.as_int()?)
+                .ok()
+                .filter(|concurrency| *concurrency > 0)
+                .ok_or_else(|| {
+                    LabeledError::new("Invalid argument")
+                        .with_label("--concurrency must be positive", span)
+                })?;
 
-            if call.get_flag_value("start").is_none() {
-                missing.push("--start");
+            query_builder.concurrency(concurrency);
+        }
+
+        let end = time_flag(call, "end")?.unwrap_or_else(|| Utc::now().fixed_offset());
+
+        let start = match (time_flag(call, "start")?, call.get_flag_value("range")) {
+            (Some(_), Some(range)) => {
+                return Err(LabeledError::new("Argument error")
+                    .with_label("Supply only --start or --range, not both", range.span()));
             }
-            if call.get_flag_value("end").is_none() {
-                missing.push("--end");
+            (Some(start), None) => start,
+            (None, Some(range)) => {
+                let span = range.span();
+                let range = range.as_duration()?;
+
+                if range <= 0 {
+                    return Err(LabeledError::new("Invalid query range")
+                        .with_label("--range must be positive", span));
+                }
+
+                end - TimeDelta::nanoseconds(range)
             }
-            if call.get_flag_value("step").is_none() {
-                missing.push("--step");
+            (None, None) => {
+                return Err(LabeledError::new("Missing query range arguments")
+                    .with_label("Missing: --start or --range", call_span));
             }
-            let missing = missing.join(", ");
-
-            return Err(LabeledError::new("Missing query range arguments")
-                .with_label(format!("Missing: {missing}"), call_span));
         };
 
-        let step = step as f64 / 1_000_000_000.0;
+        if start > end {
+            return Err(LabeledError::new("Invalid query range")
+                .with_label("--start must not be after --end", call_span));
+        }
+
+        let step = match call.get_flag::<i64>("step")? {
+            Some(step) => step as f64 / 1_000_000_000.0,
+            None => {
+                let points = match call.get_flag_value("points") {
+                    Some(points) => {
+                        let span = points.span();
+
+                        u32::try_from(points.as_int()?)
+                            .ok()
+                            .filter(|points| *points > 0)
+                            .ok_or_else(|| {
+                                LabeledError::new("Invalid argument")
+                                    .with_label("--points must be positive", span)
+                            })?
+                    }
+                    None => DEFAULT_POINTS,
+                };
+
+                auto_step(start, end, points)
+            }
+        };
 
         query_builder
             .range(start, end, step, &query, query_span, call_span)
             .run(engine.signals())
-            .map(|pipeline| {
-                let metadata = PipelineMetadata::default()
+            .map(|mut pipeline| {
+                let metadata = pipeline
+                    .take_metadata()
+                    .unwrap_or_default()
                     .with_table_width_priority_columns(call_span, ["name"]);
 
                 pipeline.set_metadata(Some(metadata))
@@ -0,0 +1,102 @@
+use crate::{
+    Prometheus,
+    client::{ApiRequest, Rules, SelectorParser},
+    source::{Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
+use prometheus_http_query::Client;
+
+#[derive(Clone, Default)]
+pub struct RulesCommand;
+
+impl PluginCommand for RulesCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus rules"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .named(
+                "type",
+                SyntaxShape::String,
+                "Only return alert or record rules",
+                None,
+            )
+            .named(
+                "rule-name",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Only return rules with these names",
+                None,
+            )
+            .named(
+                "rule-group",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Only return rules in these groups",
+                None,
+            )
+            .named(
+                "file",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "Only return rules from these files",
+                None,
+            )
+            .source_flags()
+            .rest(
+                "selectors",
+                SyntaxShape::String,
+                "Only return rules with labels matching these series selectors",
+            )
+            .input_output_type(Type::Nothing, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "Query for recording and alerting rules"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let client: Client = Source::from(call, engine)?.try_into()?;
+
+        let mut request = ApiRequest::new(client, "api/v1/rules");
+
+        if let Some(kind) = call.get_flag_value("type") {
+            let kind = match kind.as_str()? {
+                "alert" => "alert",
+                "record" => "record",
+                _ => {
+                    return Err(LabeledError::new("Invalid rule type")
+                        .with_label("Must be alert or record", kind.span()));
+                }
+            };
+
+            request = request.param("type", kind);
+        }
+
+        for (flag, param) in [
+            ("rule-name", "rule_name[]"),
+            ("rule-group", "rule_group[]"),
+            ("file", "file[]"),
+        ] {
+            for value in call.get_flag::<Vec<String>>(flag)?.unwrap_or_default() {
+                request = request.param(param, value);
+            }
+        }
+
+        for selector in call.rest(0)? {
+            request = request.param("match[]", SelectorParser::parse(&selector)?);
+        }
+
+        Rules::new(request).run(engine.signals(), call_span)
+    }
+}
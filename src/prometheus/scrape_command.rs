@@ -15,7 +15,16 @@ impl SimplePluginCommand for ScrapeCommand {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .description(self.description())
-            .input_output_type(Type::String, Type::table())
+            .switch("no-flatten", "Do not flatten labels into record", None)
+            .switch(
+                "lenient",
+                "Skip invalid lines and report them in an errors column",
+                None,
+            )
+            .input_output_types(vec![
+                (Type::String, Type::table()),
+                (Type::String, Type::record()),
+            ])
     }
 
     fn description(&self) -> &str {
@@ -26,11 +35,15 @@ impl SimplePluginCommand for ScrapeCommand {
         &self,
         _plugin: &Self::Plugin,
         _engine: &EngineInterface,
-        _call: &EvaluatedCall,
+        call: &EvaluatedCall,
         input: &Value,
     ) -> Result<Value, LabeledError> {
         let target = input.as_str()?;
 
-        Scrape::new(target.into()).run()
+        let mut scrape = Scrape::new(target.into(), input.span());
+        scrape.set_flatten(!call.has_flag("no-flatten")?);
+        scrape.set_lenient(call.has_flag("lenient")?);
+
+        scrape.run()
     }
 }
@@ -1,10 +1,11 @@
 use crate::{
     Prometheus, Source,
-    client::{SelectorParser, Series},
+    client::{ApiRequest, SelectorParser, Series},
+    source::SourceSignature,
+    time::{time_flag, time_shape},
 };
-use chrono::{DateTime, FixedOffset};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
-use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type, Value};
+use nu_protocol::{LabeledError, PipelineData, Signature, Type, Value};
 use prometheus_http_query::Client;
 
 #[derive(Clone, Default)]
@@ -20,20 +21,9 @@ impl PluginCommand for SeriesCommand {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .description(self.description())
-            .named(
-                "source",
-                SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
-            )
-            .named(
-                "url",
-                SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
-            )
-            .named("start", SyntaxShape::DateTime, "Start timestamp", None)
-            .named("end", SyntaxShape::DateTime, "End timestamp", None)
+            .source_flags()
+            .named("start", time_shape(), "Start timestamp", None)
+            .named("end", time_shape(), "End timestamp", None)
             .input_output_types(vec![
                 (Type::String, Type::List(Box::new(Type::String))),
                 (
@@ -61,30 +51,35 @@ impl PluginCommand for SeriesCommand {
 
         let client: Client = Source::from(call, engine)?.try_into()?;
 
-        let mut builder = match selectors {
-            Value::String { .. } => client.series(vec![SelectorParser::parse(&selectors)?]),
-            Value::List { vals: values, .. } => {
-                let mut selectors = vec![];
+        let mut builder = ApiRequest::new(client, "api/v1/series");
 
-                for selector in &values {
-                    selectors.push(SelectorParser::parse(selector)?);
+        match selectors {
+            Value::String { .. } => {
+                builder = builder.param("match[]", SelectorParser::parse(&selectors)?);
+            }
+            Value::List {
+                vals: ref values, ..
+            } if !values.is_empty() => {
+                for selector in values {
+                    builder = builder.param("match[]", SelectorParser::parse(selector)?);
                 }
-
-                client.series(selectors)
+            }
+            Value::List { .. } => {
+                return Err(LabeledError::new("Series query error")
+                    .with_label("at least one series selector is required", selectors_span));
             }
             _ => {
                 return Err(LabeledError::new("Invalid input type")
                     .with_label("must be Nothing, String or list of Strings", selectors_span));
             }
         }
-        .map_err(|e| LabeledError::new("Series query error").with_help(e.to_string()))?;
 
-        if let Some(start) = call.get_flag::<DateTime<FixedOffset>>("start")? {
-            builder = builder.start(start.timestamp());
+        if let Some(start) = time_flag(call, "start")? {
+            builder = builder.time("start", start);
         }
 
-        if let Some(end) = call.get_flag::<DateTime<FixedOffset>>("end")? {
-            builder = builder.end(end.timestamp());
+        if let Some(end) = time_flag(call, "end")? {
+            builder = builder.time("end", end);
         }
 
         Series::new(builder, selectors_span).run(engine.signals(), call_span)
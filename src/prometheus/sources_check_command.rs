@@ -0,0 +1,65 @@
+use crate::{
+    Prometheus,
+    client::SourcesCheck,
+    source::{Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
+use nu_protocol::{LabeledError, PipelineData, Signature, Type};
+
+#[derive(Clone, Default)]
+pub struct SourcesCheckCommand;
+
+impl PluginCommand for SourcesCheckCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus sources check"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .source_flags()
+            .input_output_type(Type::Nothing, Type::table())
+    }
+
+    fn description(&self) -> &str {
+        "Check connectivity to configured sources"
+    }
+
+    fn extra_description(&self) -> &str {
+        "Checks every configured source unless --source or --url is given.  Reports reachability, latency, prometheus version, TLS verification, and server certificate expiry.  A source that fails to load is reported in the error column."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: PipelineData,
+    ) -> Result<PipelineData, LabeledError> {
+        let call_span = call.head;
+
+        let sources =
+            if call.get_flag_value("source").is_some() || call.get_flag_value("url").is_some() {
+                let source = Source::from(call, engine)?;
+
+                vec![(source.name.clone(), Ok(source))]
+            } else {
+                Source::list(engine)?
+                    .into_iter()
+                    .map(|(name, source)| {
+                        let source = source.and_then(|mut source| {
+                            source.options.update_from_call(call)?;
+
+                            Ok(source)
+                        });
+
+                        (Some(name), source)
+                    })
+                    .collect()
+            };
+
+        SourcesCheck::new(sources).run(engine.signals(), call_span)
+    }
+}
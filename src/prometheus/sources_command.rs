@@ -1,6 +1,9 @@
-use crate::{source::Source, Prometheus};
+use crate::{
+    Prometheus,
+    source::{Source, Summary},
+};
 use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
-use nu_protocol::{LabeledError, Signature, Type, Value};
+use nu_protocol::{LabeledError, Signature, Span, Type, Value, record};
 
 #[derive(Clone, Default)]
 pub struct SourcesCommand;
@@ -22,21 +25,40 @@ impl SimplePluginCommand for SourcesCommand {
         "List configured sources"
     }
 
+    fn extra_description(&self) -> &str {
+        "Paths and secrets are not shown.  The cert, key, and cacert columns show whether each configured item loaded.  A source that fails to load is reported in the error column."
+    }
+
     fn run(
         &self,
         _plugin: &Prometheus,
         engine: &EngineInterface,
-        _call: &EvaluatedCall,
+        call: &EvaluatedCall,
         _input: &Value,
     ) -> Result<Value, LabeledError> {
-        // validate
-        Source::list(engine)?;
-
-        Ok(engine
-            .get_plugin_config()
-            .expect("already validated by Source::list()")
-            .unwrap()
-            .get_data_by_key("sources")
-            .unwrap())
+        let span = call.head;
+
+        let summaries = Source::summaries(engine)?
+            .into_iter()
+            .map(|summary| summary_to_value(summary, span))
+            .collect();
+
+        Ok(Value::list(summaries, span))
     }
 }
+
+fn summary_to_value(summary: Summary, span: Span) -> Value {
+    let optional = |value: Option<Value>| value.unwrap_or_else(|| Value::nothing(span));
+
+    let record = record! {
+        "name" => Value::string(summary.name, span),
+        "url" => optional(summary.url.map(|url| Value::string(url, span))),
+        "auth" => optional(summary.auth.map(|auth| Value::string(auth, span))),
+        "cert" => optional(summary.cert.map(|cert| Value::bool(cert, span))),
+        "key" => optional(summary.key.map(|key| Value::bool(key, span))),
+        "cacert" => optional(summary.cacert.map(|cacert| Value::bool(cacert, span))),
+        "error" => optional(summary.error.map(|error| Value::string(error, span))),
+    };
+
+    Value::record(record, span)
+}
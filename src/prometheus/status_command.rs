@@ -0,0 +1,97 @@
+use crate::{
+    Prometheus,
+    client::{Status, StatusEndpoint},
+    source::{Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, Span, Type, Value};
+
+#[derive(Clone, Default)]
+pub struct StatusCommand;
+
+impl SimplePluginCommand for StatusCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus status"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name()).input_output_type(Type::Nothing, Type::Nothing)
+    }
+
+    fn description(&self) -> &str {
+        "Prometheus server status"
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        _call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        eprintln!("{}", engine.get_help()?);
+
+        Ok(Value::nothing(Span::unknown()))
+    }
+}
+
+/// A `prometheus status` subcommand for one status endpoint
+#[derive(Clone)]
+pub struct StatusEndpointCommand {
+    endpoint: StatusEndpoint,
+    name: String,
+}
+
+impl StatusEndpointCommand {
+    pub fn new(endpoint: StatusEndpoint) -> Self {
+        Self {
+            endpoint,
+            name: format!("prometheus status {}", endpoint.name()),
+        }
+    }
+}
+
+impl SimplePluginCommand for StatusEndpointCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .source_flags()
+            .input_output_type(Type::Nothing, Type::record())
+    }
+
+    fn description(&self) -> &str {
+        match self.endpoint {
+            StatusEndpoint::BuildInfo => "Query for server build information",
+            StatusEndpoint::RuntimeInfo => "Query for server runtime information",
+            StatusEndpoint::Flags => "Query for server command line flags",
+            StatusEndpoint::Config => "Query for the loaded server configuration",
+        }
+    }
+
+    fn extra_description(&self) -> &str {
+        match self.endpoint {
+            StatusEndpoint::Config => "The YAML configuration is parsed into a record.",
+            _ => "",
+        }
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let source = Source::from(call, engine)?;
+
+        Status::new(source.try_into()?, self.endpoint).run(engine.signals(), call.head)
+    }
+}
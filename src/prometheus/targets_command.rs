@@ -1,4 +1,8 @@
-use crate::{Prometheus, client::Targets, source::Source};
+use crate::{
+    Prometheus,
+    client::Targets,
+    source::{Source, SourceSignature},
+};
 use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand};
 use nu_protocol::{LabeledError, PipelineData, Signature, SyntaxShape, Type};
 use prometheus_http_query::TargetState;
@@ -16,18 +20,7 @@ impl PluginCommand for TargetsCommand {
     fn signature(&self) -> Signature {
         Signature::build(self.name())
             .description(self.description())
-            .named(
-                "source",
-                SyntaxShape::String,
-                "Prometheus source to query",
-                Some('s'),
-            )
-            .named(
-                "url",
-                SyntaxShape::String,
-                "Prometheus source url to query",
-                Some('u'),
-            )
+            .source_flags()
             .optional("state", SyntaxShape::String, "Target state filter")
             .input_output_types(vec![
                 (Type::Nothing, Type::record()),
@@ -0,0 +1,58 @@
+use crate::{
+    Prometheus,
+    exposition::{self, Format},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, Type, Value};
+
+/// A `to` command serializing samples in one exposition format
+#[derive(Clone)]
+pub struct ToCommand {
+    format: Format,
+}
+
+impl ToCommand {
+    pub fn new(format: Format) -> Self {
+        Self { format }
+    }
+}
+
+impl SimplePluginCommand for ToCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        match self.format {
+            Format::Prometheus => "to prometheus",
+            Format::Openmetrics => "to openmetrics",
+        }
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .input_output_type(Type::table(), Type::String)
+    }
+
+    fn description(&self) -> &str {
+        match self.format {
+            Format::Prometheus => "Convert samples to the Prometheus text exposition format",
+            Format::Openmetrics => "Convert samples to the OpenMetrics text exposition format",
+        }
+    }
+
+    fn extra_description(&self) -> &str {
+        "The input is the output of `prometheus parse` or a table of samples.  Each sample has a name, value, and optional labels record, type, help, unit, and timestamp.  Any other columns are labels, and labels named like these columns must be in the labels record.  Samples are grouped by metric family, with _bucket, _count, and _sum samples joining their histogram or summary."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let text = exposition::to_text(input, self.format)?;
+
+        Ok(Value::string(text, call.head))
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    Prometheus,
+    client::{ApiRequest, SelectorParser, TsdbStats},
+    source::{Source, SourceSignature},
+};
+use nu_plugin::{EngineInterface, EvaluatedCall, SimplePluginCommand};
+use nu_protocol::{LabeledError, Signature, SyntaxShape, Type, Value};
+use prometheus_http_query::Client;
+
+#[derive(Clone, Default)]
+pub struct TsdbStatsCommand;
+
+impl SimplePluginCommand for TsdbStatsCommand {
+    type Plugin = Prometheus;
+
+    fn name(&self) -> &str {
+        "prometheus tsdb stats"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(self.name())
+            .description(self.description())
+            .named(
+                "limit",
+                SyntaxShape::Int,
+                "Number of items in each top list, default 10",
+                None,
+            )
+            .source_flags()
+            .input_output_types(vec![
+                (Type::Nothing, Type::record()),
+                (Type::String, Type::record()),
+            ])
+    }
+
+    fn description(&self) -> &str {
+        "Query for TSDB cardinality statistics"
+    }
+
+    fn extra_description(&self) -> &str {
+        "A series selector given as input limits the statistics to matching series on servers that support it."
+    }
+
+    fn run(
+        &self,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let client: Client = Source::from(call, engine)?.try_into()?;
+
+        let mut request = ApiRequest::new(client, "api/v1/status/tsdb");
+
+        if let Some(limit) = call.get_flag_value("limit") {
+            let span = limit.span();
+
+            let limit = u32::try_from(limit.as_int()?)
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| {
+                    LabeledError::new("Invalid argument")
+                        .with_label("--limit must be positive", span)
+                })?;
+
+            request = request.param("limit", limit);
+        }
+
+        match input {
+            Value::Nothing { .. } => (),
+            Value::String { .. } => {
+                request = request.param("match[]", SelectorParser::parse(input)?);
+            }
+            _ => {
+                return Err(LabeledError::new("Invalid input type")
+                    .with_label("must be Nothing or String", input.span()));
+            }
+        }
+
+        TsdbStats::new(request).run(engine.signals(), call.head)
+    }
+}
@@ -1,10 +1,188 @@
+use crate::client::{
+    Histogram, InstantVector, Point, RangeVector, ResultType, Stats, StringSample,
+};
+use chrono::DateTime;
 use nu_protocol::{
-    IntoInterruptiblePipelineData, PipelineData, Record, Signals, Span, Value, record,
+    IntoInterruptiblePipelineData, IntoPipelineData, LabeledError, PipelineData, Record, Signals,
+    Span, Value, record,
 };
-use prometheus_http_query::response::{InstantVector, RangeVector, Sample};
+use prometheus_http_query::response::Sample;
 use std::collections::HashMap;
 
-fn add_labels(record: &mut Record, metric: &HashMap<String, String>, flatten: bool, span: Span) {
+/// Output format of sample timestamps
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Timestamps {
+    #[default]
+    DateTime,
+    /// Unix seconds
+    Float,
+    /// Unix milliseconds, the precision Prometheus stores
+    Int,
+}
+
+impl Timestamps {
+    pub fn from_value(value: &Value) -> Result<Self, LabeledError> {
+        match value.as_str()? {
+            "datetime" => Ok(Timestamps::DateTime),
+            "float" => Ok(Timestamps::Float),
+            "int" => Ok(Timestamps::Int),
+            _ => Err(LabeledError::new("Invalid timestamps format")
+                .with_label("Must be datetime, float, or int", value.span())),
+        }
+    }
+
+    pub fn to_value(self, timestamp: f64, span: Span) -> Value {
+        let millis = (timestamp * 1000.0).round() as i64;
+
+        match self {
+            Timestamps::DateTime => match DateTime::from_timestamp_millis(millis) {
+                Some(time) => Value::date(time.fixed_offset(), span),
+                None => Value::float(timestamp, span),
+            },
+            Timestamps::Float => Value::float(timestamp, span),
+            Timestamps::Int => Value::int(millis, span),
+        }
+    }
+}
+
+/// Options for the output of a query result
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QueryOptions {
+    pub flatten: bool,
+    pub timestamps: Timestamps,
+    /// Fail unless the query returns this result type
+    pub result_type: Option<ResultType>,
+    pub annotations: Annotations,
+}
+
+/// Custom pipeline metadata key holding the warnings returned with a result
+const WARNINGS_METADATA_KEY: &str = "prometheus_warnings";
+
+/// Custom pipeline metadata key holding the infos returned with a result
+const INFOS_METADATA_KEY: &str = "prometheus_infos";
+
+/// How warnings and infos returned with a query result are reported.
+///
+/// By default they are added to the custom pipeline metadata of the result.
+/// The result is wrapped in a record when they are wrapped with it instead, or
+/// when query statistics are returned.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Annotations {
+    /// Wrap the result in a record with the warnings and infos
+    pub wrap: bool,
+    /// Fail if there are any warnings
+    pub fail_on_warning: bool,
+}
+
+impl Annotations {
+    pub fn apply(
+        self,
+        data: PipelineData,
+        warnings: Vec<String>,
+        infos: Vec<String>,
+        stats: Option<Value>,
+        query_span: Span,
+        call_span: Span,
+    ) -> Result<PipelineData, LabeledError> {
+        if self.fail_on_warning && !warnings.is_empty() {
+            return Err(warnings.iter().fold(
+                LabeledError::new("Query returned warnings"),
+                |error, warning| error.with_label(warning, query_span),
+            ));
+        }
+
+        let strings = |strings: Vec<String>| {
+            let strings = strings
+                .into_iter()
+                .map(|string| Value::string(string, call_span))
+                .collect();
+
+            Value::list(strings, call_span)
+        };
+
+        let warnings = strings(warnings);
+        let infos = strings(infos);
+
+        if self.wrap {
+            let mut record = record! {
+                "result" => data.into_value(call_span)?,
+                "warnings" => warnings,
+                "infos" => infos,
+            };
+
+            if let Some(stats) = stats {
+                record.push("stats", stats);
+            }
+
+            return Ok(Value::record(record, call_span).into_pipeline_data());
+        }
+
+        let mut data = match stats {
+            Some(stats) => {
+                let record = record! {
+                    "result" => data.into_value(call_span)?,
+                    "stats" => stats,
+                };
+
+                Value::record(record, call_span).into_pipeline_data()
+            }
+            None => data,
+        };
+
+        let mut metadata = data.take_metadata().unwrap_or_default();
+        metadata.custom.push(WARNINGS_METADATA_KEY, warnings);
+        metadata.custom.push(INFOS_METADATA_KEY, infos);
+
+        Ok(data.set_metadata(Some(metadata)))
+    }
+}
+
+pub fn stats_to_value(stats: &Stats, timestamps: Timestamps, span: Span) -> Value {
+    let seconds = |seconds: f64| Value::duration((seconds * 1_000_000_000.0) as i64, span);
+
+    let timings = &stats.timings;
+    let samples = &stats.samples;
+
+    let per_step = samples
+        .total_queryable_samples_per_step
+        .iter()
+        .map(|(timestamp, samples)| {
+            Value::record(
+                record! {
+                    "timestamp" => timestamps.to_value(*timestamp, span),
+                    "samples" => Value::int(*samples, span),
+                },
+                span,
+            )
+        })
+        .collect();
+
+    Value::record(
+        record! {
+            "timings" => Value::record(record! {
+                "eval_total_time" => seconds(timings.eval_total_time),
+                "result_sort_time" => seconds(timings.result_sort_time),
+                "query_preparation_time" => seconds(timings.query_preparation_time),
+                "inner_eval_time" => seconds(timings.inner_eval_time),
+                "exec_queue_time" => seconds(timings.exec_queue_time),
+                "exec_total_time" => seconds(timings.exec_total_time),
+            }, span),
+            "samples" => Value::record(record! {
+                "total_queryable_samples" => Value::int(samples.total_queryable_samples, span),
+                "peak_samples" => Value::int(samples.peak_samples, span),
+                "total_queryable_samples_per_step" => Value::list(per_step, span),
+            }, span),
+        },
+        span,
+    )
+}
+
+pub fn add_labels(
+    record: &mut Record,
+    metric: &HashMap<String, String>,
+    flatten: bool,
+    span: Span,
+) {
     if flatten {
         for (name, label) in metric {
             if name == "__name__" {
@@ -26,20 +204,24 @@ fn add_labels(record: &mut Record, metric: &HashMap<String, String>, flatten: bo
     }
 }
 
+/// Converts the matrices from the chunks of a range query to a value.
+///
+/// The samples of each series are stitched together in chunk order with
+/// samples at chunk boundaries de-duplicated.  Float samples have a `value`
+/// and histogram samples a `histogram`.
 pub fn matrix_to_value(
-    matrix: Vec<RangeVector>,
+    matrices: Vec<Vec<RangeVector>>,
     flatten: bool,
+    timestamps: Timestamps,
     span: Span,
     signals: &Signals,
 ) -> PipelineData {
-    matrix
+    stitch(matrices)
         .into_iter()
-        .map(move |rv| {
-            let metric = rv.metric();
-            let values = rv
-                .samples()
+        .map(move |(metric, samples)| {
+            let values = samples
                 .iter()
-                .map(|value| scalar_to_value(value, span))
+                .map(|point| point_to_value(point, timestamps, span))
                 .collect();
 
             let name = metric
@@ -51,7 +233,7 @@ pub fn matrix_to_value(
                 "name" => Value::string(name, span),
             };
 
-            add_labels(&mut record, metric, flatten, span);
+            add_labels(&mut record, &metric, flatten, span);
 
             record.insert("values", Value::list(values, span));
 
@@ -60,11 +242,123 @@ pub fn matrix_to_value(
         .into_pipeline_data(span, signals.clone())
 }
 
-pub fn scalar_to_value(scalar: &Sample, span: Span) -> Value {
+fn stitch(matrices: Vec<Vec<RangeVector>>) -> Vec<(HashMap<String, String>, Vec<Point>)> {
+    let mut series: Vec<(HashMap<String, String>, Vec<Point>)> = vec![];
+    let mut index: HashMap<Vec<(String, String)>, usize> = HashMap::new();
+
+    for matrix in matrices {
+        for range_vector in matrix {
+            let (metric, samples) = range_vector.into_inner();
+
+            let mut key: Vec<_> = metric.clone().into_iter().collect();
+            key.sort();
+
+            let Some(&i) = index.get(&key) else {
+                index.insert(key, series.len());
+                series.push((metric, samples));
+                continue;
+            };
+
+            let stitched = &mut series[i].1;
+            let last = stitched.last().map(|sample| sample.timestamp());
+
+            stitched.extend(
+                samples
+                    .into_iter()
+                    .filter(|sample| last.is_none_or(|last| sample.timestamp() > last)),
+            );
+        }
+    }
+
+    series
+}
+
+/// Checks that a query returned the `expected` result type, if any
+pub fn check_result_type(
+    expected: Option<ResultType>,
+    actual: ResultType,
+    span: Span,
+) -> Result<(), LabeledError> {
+    match expected {
+        Some(expected) if expected != actual => Err(LabeledError::new("Unexpected result type")
+            .with_label(format!("expected {expected}, got {actual}"), span)),
+        _ => Ok(()),
+    }
+}
+
+fn point_to_value(point: &Point, timestamps: Timestamps, span: Span) -> Value {
+    let mut record = Record::new();
+
+    add_point(&mut record, point, timestamps, span);
+
+    Value::record(record, span)
+}
+
+/// Adds the `value` or `histogram` and `timestamp` of `point` to `record`
+fn add_point(record: &mut Record, point: &Point, timestamps: Timestamps, span: Span) {
+    match point {
+        Point::Float(sample) => record.push("value", Value::float(sample.value(), span)),
+        Point::Histogram(sample) => {
+            record.push("histogram", histogram_to_value(sample.histogram(), span))
+        }
+    }
+
+    record.push("timestamp", timestamps.to_value(point.timestamp(), span));
+}
+
+fn histogram_to_value(histogram: &Histogram, span: Span) -> Value {
+    let buckets = histogram
+        .buckets()
+        .iter()
+        .map(|bucket| {
+            Value::record(
+                record! {
+                    "lower" => Value::float(bucket.lower(), span),
+                    "upper" => Value::float(bucket.upper(), span),
+                    "lower_inclusive" => Value::bool(bucket.lower_inclusive(), span),
+                    "upper_inclusive" => Value::bool(bucket.upper_inclusive(), span),
+                    "count" => Value::float(bucket.count(), span),
+                },
+                span,
+            )
+        })
+        .collect();
+
+    let inferred_schema = histogram
+        .inferred_schema()
+        .map_or(Value::nothing(span), |schema| Value::int(schema, span));
+
+    Value::record(
+        record! {
+            "count" => Value::float(histogram.count(), span),
+            "sum" => Value::float(histogram.sum(), span),
+            "inferred_schema" => inferred_schema,
+            "buckets" => Value::list(buckets, span),
+        },
+        span,
+    )
+}
+
+/// Converts a scalar result to a value.
+///
+/// NaN and infinite values are kept as floats.
+pub fn scalar_to_value(scalar: &Sample, timestamps: Timestamps, span: Span) -> Value {
     Value::record(
         record! {
+            "result_type" => Value::string(ResultType::Scalar.to_string(), span),
             "value" => Value::float(scalar.value(), span),
-            "timestamp" => Value::float(scalar.timestamp(), span)
+            "timestamp" => timestamps.to_value(scalar.timestamp(), span),
+        },
+        span,
+    )
+}
+
+pub fn string_to_value(string: &StringSample, timestamps: Timestamps, span: Span) -> Value {
+    Value::record(
+        record! {
+            "result_type" => Value::string(ResultType::String.to_string(), span),
+            "value" => Value::string(string.value(), span),
+            "timestamp" => timestamps.to_value(string.timestamp(), span),
         },
         span,
     )
@@ -73,6 +367,7 @@ pub fn scalar_to_value(scalar: &Sample, span: Span) -> Value {
 pub fn vector_to_value(
     vector: Vec<InstantVector>,
     flatten: bool,
+    timestamps: Timestamps,
     span: Span,
     signals: &Signals,
 ) -> PipelineData {
@@ -92,11 +387,7 @@ pub fn vector_to_value(
 
             add_labels(&mut record, metric, flatten, span);
 
-            let value = Value::float(iv.sample().value(), span);
-            record.insert("value", value);
-
-            let timestamp = Value::float(iv.sample().timestamp(), span);
-            record.insert("timestamp", timestamp);
+            add_point(&mut record, iv.point(), timestamps, span);
 
             Value::record(record, span)
         })
@@ -105,8 +396,11 @@ pub fn vector_to_value(
 
 #[cfg(test)]
 mod test {
-    use nu_protocol::{Signals, Span, Value, record};
-    use prometheus_http_query::response::{InstantVector, RangeVector, Sample};
+    use super::{Annotations, Timestamps};
+    use crate::client::{InstantVector, RangeVector, ResultType, Stats, StringSample};
+    use nu_protocol::{IntoPipelineData, Signals, Span, Value, record};
+    use prometheus_http_query::response::Sample;
+    use rstest::rstest;
     use std::collections::HashMap;
 
     #[test]
@@ -182,7 +476,13 @@ mod test {
         .as_bytes();
         let matrix: Vec<RangeVector> = serde_json::from_slice(data).unwrap();
 
-        let result = super::matrix_to_value(matrix, false, Span::unknown(), &Signals::empty());
+        let result = super::matrix_to_value(
+            vec![matrix],
+            false,
+            Timestamps::Float,
+            Span::unknown(),
+            &Signals::empty(),
+        );
 
         let record = result
             .into_value(Span::unknown())
@@ -206,15 +506,56 @@ mod test {
         assert_eq!(3, values.len());
     }
 
+    #[test]
+    fn matrix_to_value_stitch() {
+        let first: Vec<RangeVector> = serde_json::from_str(
+            r#"[
+              {"metric":{"__name__":"up","job":"prometheus"},"values":[[0,"1"],[15,"1"]]},
+              {"metric":{"__name__":"up","job":"node"},"values":[[0,"0"],[15,"0"]]}
+            ]"#,
+        )
+        .unwrap();
+        let second: Vec<RangeVector> = serde_json::from_str(
+            r#"[
+              {"metric":{"job":"node","__name__":"up"},"values":[[15,"0"],[30,"1"]]},
+              {"metric":{"__name__":"up","job":"prometheus"},"values":[[15,"1"],[30,"1"]]},
+              {"metric":{"__name__":"up","job":"new"},"values":[[30,"1"]]}
+            ]"#,
+        )
+        .unwrap();
+
+        let series: Vec<_> = super::stitch(vec![first, second])
+            .into_iter()
+            .map(|(metric, samples)| {
+                let timestamps: Vec<_> = samples.iter().map(|sample| sample.timestamp()).collect();
+
+                (metric.get("job").unwrap().clone(), timestamps)
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("prometheus".to_string(), vec![0.0, 15.0, 30.0]),
+                ("node".to_string(), vec![0.0, 15.0, 30.0]),
+                ("new".to_string(), vec![30.0]),
+            ],
+            series
+        );
+    }
+
     #[test]
     fn scalar_to_value() {
         let data = r#"[1716956024.754,"1"]"#.as_bytes();
         let scalar: Sample = serde_json::from_slice(data).unwrap();
 
-        let result = super::scalar_to_value(&scalar, Span::unknown())
+        let result = super::scalar_to_value(&scalar, Timestamps::Float, Span::unknown())
             .into_record()
             .unwrap();
 
+        assert_eq!(
+            "scalar",
+            result.get("result_type").unwrap().as_str().unwrap()
+        );
         assert_eq!(1.0, result.get("value").unwrap().as_float().unwrap());
         assert_eq!(
             1716956024,
@@ -222,16 +563,79 @@ mod test {
         );
     }
 
+    #[rstest]
+    #[case("NaN")]
+    #[case("+Inf")]
+    #[case("-Inf")]
+    fn scalar_to_value_special(#[case] input: &str) {
+        let data = format!(r#"[1716956024.754,"{input}"]"#);
+        let scalar: Sample = serde_json::from_str(&data).unwrap();
+
+        let result = super::scalar_to_value(&scalar, Timestamps::Float, Span::unknown())
+            .into_record()
+            .unwrap();
+
+        let value = result.get("value").unwrap().as_float().unwrap();
+        let expected: f64 = input.parse().unwrap();
+
+        if expected.is_nan() {
+            assert!(value.is_nan());
+        } else {
+            assert_eq!(expected, value);
+        }
+    }
+
+    #[test]
+    fn string_to_value() {
+        let string: StringSample = serde_json::from_str(r#"[1716956024.754,"foo"]"#).unwrap();
+
+        let result = super::string_to_value(&string, Timestamps::Float, Span::unknown())
+            .into_record()
+            .unwrap();
+
+        assert_eq!(
+            "string",
+            result.get("result_type").unwrap().as_str().unwrap()
+        );
+        assert_eq!("foo", result.get("value").unwrap().as_str().unwrap());
+        assert_eq!(
+            1716956024.754,
+            result.get("timestamp").unwrap().as_float().unwrap()
+        );
+    }
+
+    #[test]
+    fn check_result_type() {
+        let span = Span::test_data();
+
+        assert!(super::check_result_type(None, ResultType::Matrix, span).is_ok());
+        assert!(
+            super::check_result_type(Some(ResultType::Matrix), ResultType::Matrix, span).is_ok()
+        );
+
+        let err = super::check_result_type(Some(ResultType::Vector), ResultType::Matrix, span)
+            .unwrap_err();
+
+        assert_eq!("Unexpected result type", err.msg);
+        assert_eq!("expected vector, got matrix", err.labels[0].text);
+    }
+
     #[test]
     fn vector_to_value() {
         let data = r#"[{"metric":{"__name__":"up","instance":"target.example","job":"job name"},"value":[1716956024.754,"1"]}]"#.as_bytes();
         let vector: Vec<InstantVector> = serde_json::from_slice(data).unwrap();
 
-        let result = super::vector_to_value(vector, false, Span::unknown(), &Signals::empty())
-            .into_value(Span::unknown())
-            .unwrap()
-            .into_list()
-            .unwrap();
+        let result = super::vector_to_value(
+            vector,
+            false,
+            Timestamps::DateTime,
+            Span::unknown(),
+            &Signals::empty(),
+        )
+        .into_value(Span::unknown())
+        .unwrap()
+        .into_list()
+        .unwrap();
 
         let record = result.first().unwrap().as_record().unwrap();
 
@@ -245,8 +649,317 @@ mod test {
 
         assert_eq!(1.0, value);
 
-        let timestamp = record.get("timestamp").unwrap().as_float().unwrap();
+        let timestamp = record.get("timestamp").unwrap().as_date().unwrap();
+
+        assert_eq!(1716956024754, timestamp.timestamp_millis());
+    }
+
+    #[test]
+    fn vector_to_value_histogram() {
+        let data = r#"[{"metric":{"__name__":"rpc_seconds","job":"rpc"},"histogram":[1716956024.754,{"count":"4","sum":"2.5","buckets":[[0,"0.5","1","1"],[0,"1","2","3"]]}]}]"#;
+        let vector: Vec<InstantVector> = serde_json::from_str(data).unwrap();
+
+        let result = super::vector_to_value(
+            vector,
+            true,
+            Timestamps::DateTime,
+            Span::unknown(),
+            &Signals::empty(),
+        )
+        .into_value(Span::unknown())
+        .unwrap()
+        .into_list()
+        .unwrap();
+
+        let record = result.first().unwrap().as_record().unwrap();
+
+        assert_eq!("rpc_seconds", record.get("name").unwrap().as_str().unwrap());
+        assert!(record.get("value").is_none());
+
+        let histogram = record.get("histogram").unwrap().as_record().unwrap();
+
+        assert_eq!(4.0, histogram.get("count").unwrap().as_float().unwrap());
+        assert_eq!(2.5, histogram.get("sum").unwrap().as_float().unwrap());
+        assert_eq!(
+            0,
+            histogram.get("inferred_schema").unwrap().as_int().unwrap()
+        );
+
+        let buckets = histogram.get("buckets").unwrap().as_list().unwrap();
+        let expected = Value::test_record(record! {
+            "lower" => Value::test_float(1.0),
+            "upper" => Value::test_float(2.0),
+            "lower_inclusive" => Value::test_bool(false),
+            "upper_inclusive" => Value::test_bool(true),
+            "count" => Value::test_float(3.0),
+        });
+
+        assert_eq!(2, buckets.len());
+        assert_eq!(expected, buckets[1]);
+    }
+
+    #[test]
+    fn matrix_to_value_histogram() {
+        let matrix: Vec<RangeVector> = serde_json::from_str(
+            r#"[{
+              "metric":{"__name__":"rpc_seconds"},
+              "values":[[30,"1"]],
+              "histograms":[[15,{"count":"1","sum":"1"}],[45,{"count":"2","sum":"2"}]]
+            }]"#,
+        )
+        .unwrap();
+
+        let result = super::matrix_to_value(
+            vec![matrix],
+            false,
+            Timestamps::Float,
+            Span::unknown(),
+            &Signals::empty(),
+        )
+        .into_value(Span::unknown())
+        .unwrap()
+        .into_list()
+        .unwrap();
+
+        let record = result.first().unwrap().as_record().unwrap();
+        let values = record.get("values").unwrap().as_list().unwrap();
+
+        let columns: Vec<_> = values
+            .iter()
+            .map(|value| {
+                let value = value.as_record().unwrap();
+
+                (
+                    value.columns().next().unwrap().clone(),
+                    value.get("timestamp").unwrap().as_float().unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("histogram".to_string(), 15.0),
+                ("value".to_string(), 30.0),
+                ("histogram".to_string(), 45.0),
+            ],
+            columns
+        );
+    }
+
+    #[test]
+    fn annotations_wrap() {
+        let annotations = Annotations {
+            wrap: true,
+            fail_on_warning: false,
+        };
+
+        let result = annotations
+            .apply(
+                Value::test_int(1).into_pipeline_data(),
+                vec!["partial response".into()],
+                vec!["metric might not be a counter".into()],
+                None,
+                Span::test_data(),
+                Span::test_data(),
+            )
+            .unwrap()
+            .into_value(Span::test_data())
+            .unwrap();
+
+        let expected = Value::test_record(record! {
+            "result" => Value::test_int(1),
+            "warnings" => Value::test_list(vec![Value::test_string("partial response")]),
+            "infos" => Value::test_list(vec![Value::test_string("metric might not be a counter")]),
+        });
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn annotations_fail_on_warning() {
+        let annotations = Annotations {
+            wrap: true,
+            fail_on_warning: true,
+        };
+
+        let err = annotations
+            .apply(
+                Value::test_int(1).into_pipeline_data(),
+                vec!["partial response".into()],
+                vec![],
+                None,
+                Span::test_data(),
+                Span::test_data(),
+            )
+            .unwrap_err();
+
+        assert_eq!("Query returned warnings", err.msg);
+        assert_eq!("partial response", err.labels[0].text);
+    }
+
+    #[test]
+    fn annotations_fail_on_warning_infos() {
+        let annotations = Annotations {
+            wrap: false,
+            fail_on_warning: true,
+        };
+
+        let result = annotations
+            .apply(
+                Value::test_int(1).into_pipeline_data(),
+                vec![],
+                vec!["metric might not be a counter".into()],
+                None,
+                Span::test_data(),
+                Span::test_data(),
+            )
+            .unwrap()
+            .into_value(Span::test_data())
+            .unwrap();
+
+        assert_eq!(Value::test_int(1), result);
+    }
+
+    #[test]
+    fn annotations_metadata() {
+        let result = Annotations::default()
+            .apply(
+                Value::test_int(1).into_pipeline_data(),
+                vec!["partial response".into()],
+                vec!["metric might not be a counter".into()],
+                None,
+                Span::test_data(),
+                Span::test_data(),
+            )
+            .unwrap();
+
+        let metadata = result.metadata_ref().unwrap().clone();
+
+        let expected = record! {
+            "prometheus_warnings" => Value::test_list(vec![Value::test_string("partial response")]),
+            "prometheus_infos" => Value::test_list(vec![Value::test_string("metric might not be a counter")]),
+        };
+
+        assert_eq!(expected, metadata.custom);
+        assert_eq!(
+            Value::test_int(1),
+            result.into_value(Span::test_data()).unwrap()
+        );
+    }
+
+    #[test]
+    fn annotations_stats() {
+        let result = Annotations::default()
+            .apply(
+                Value::test_int(1).into_pipeline_data(),
+                vec![],
+                vec![],
+                Some(Value::test_record(record! {})),
+                Span::test_data(),
+                Span::test_data(),
+            )
+            .unwrap()
+            .into_value(Span::test_data())
+            .unwrap();
+
+        let expected = Value::test_record(record! {
+            "result" => Value::test_int(1),
+            "stats" => Value::test_record(record! {}),
+        });
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn stats_to_value() {
+        let stats: Stats = serde_json::from_str(
+            r#"{"timings":{"evalTotalTime":0.5,"execQueueTime":0.000012},"samples":{"totalQueryableSamplesPerStep":[[1716956024.754,2]],"totalQueryableSamples":2,"peakSamples":1}}"#,
+        )
+        .unwrap();
+
+        let value = super::stats_to_value(&stats, Timestamps::Int, Span::test_data());
+
+        let timings = value.get_data_by_key("timings").unwrap();
+
+        assert_eq!(
+            500_000_000,
+            timings
+                .get_data_by_key("eval_total_time")
+                .unwrap()
+                .as_duration()
+                .unwrap()
+        );
+        assert_eq!(
+            12_000,
+            timings
+                .get_data_by_key("exec_queue_time")
+                .unwrap()
+                .as_duration()
+                .unwrap()
+        );
+
+        let samples = value.get_data_by_key("samples").unwrap();
+
+        assert_eq!(
+            2,
+            samples
+                .get_data_by_key("total_queryable_samples")
+                .unwrap()
+                .as_int()
+                .unwrap()
+        );
+        assert_eq!(
+            1,
+            samples
+                .get_data_by_key("peak_samples")
+                .unwrap()
+                .as_int()
+                .unwrap()
+        );
+
+        let per_step = samples
+            .get_data_by_key("total_queryable_samples_per_step")
+            .unwrap();
+        let expected = Value::test_list(vec![Value::test_record(record! {
+            "timestamp" => Value::test_int(1716956024754),
+            "samples" => Value::test_int(2),
+        })]);
+
+        assert_eq!(expected, per_step);
+    }
+
+    #[rstest]
+    #[case("datetime", Timestamps::DateTime)]
+    #[case("float", Timestamps::Float)]
+    #[case("int", Timestamps::Int)]
+    fn timestamps_from_value(#[case] input: &str, #[case] expected: Timestamps) {
+        assert_eq!(
+            expected,
+            Timestamps::from_value(&Value::test_string(input)).unwrap()
+        );
+    }
+
+    #[test]
+    fn timestamps_from_value_invalid() {
+        let err = Timestamps::from_value(&Value::test_string("iso")).unwrap_err();
+
+        assert_eq!("Invalid timestamps format", err.msg);
+    }
+
+    #[test]
+    fn timestamps_to_value() {
+        let span = Span::test_data();
+
+        let date = Timestamps::DateTime.to_value(1716956024.754, span);
+        assert_eq!(
+            "2024-05-29T04:13:44.754+00:00",
+            date.as_date().unwrap().to_rfc3339()
+        );
+
+        let float = Timestamps::Float.to_value(1716956024.754, span);
+        assert_eq!(1716956024.754, float.as_float().unwrap());
 
-        assert_eq!(1716956024, timestamp as u64);
+        let int = Timestamps::Int.to_value(1716956024.754, span);
+        assert_eq!(1716956024754, int.as_int().unwrap());
     }
 }
@@ -0,0 +1,414 @@
+use super::string_from_source;
+use nu_plugin::EvaluatedCall;
+use nu_protocol::{LabeledError, Record, Span, Value};
+use reqwest::{
+    ClientBuilder, NoProxy, Proxy, Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    tls::Version,
+};
+use std::time::Duration;
+
+/// HTTP client options for reaching a source
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientOptions {
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    pub request_timeout: Option<Duration>,
+    pub insecure_skip_verify: bool,
+    pub tls_server_name: Option<String>,
+    pub min_tls_version: Option<Version>,
+}
+
+impl ClientOptions {
+    pub fn from_source(source: &Record, source_name: &str) -> Result<Self, LabeledError> {
+        let insecure_skip_verify = match source.get("insecure_skip_verify") {
+            Some(value) => value.as_bool().map_err(|_| {
+                LabeledError::new("Invalid plugin configuration").with_label(
+                    format!(r#"Source {source_name:?} field "insecure_skip_verify" is not a bool"#),
+                    value.span(),
+                )
+            })?,
+            None => false,
+        };
+
+        let min_tls_version = string_from_source(source, source_name, "min_tls_version")?
+            .map(|version| tls_version(&version, source.get("min_tls_version").unwrap().span()))
+            .transpose()?;
+
+        Ok(Self {
+            proxy: string_from_source(source, source_name, "proxy")?,
+            no_proxy: string_from_source(source, source_name, "no_proxy")?,
+            connect_timeout: duration_from_source(source, source_name, "connect_timeout")?,
+            request_timeout: duration_from_source(source, source_name, "request_timeout")?,
+            insecure_skip_verify,
+            tls_server_name: string_from_source(source, source_name, "tls_server_name")?,
+            min_tls_version,
+        })
+    }
+
+    /// Overrides options with those given as flags
+    pub fn update_from_call(&mut self, call: &EvaluatedCall) -> Result<(), LabeledError> {
+        if let Some(proxy) = call.get_flag_value("proxy") {
+            self.proxy = Some(proxy.into_string()?);
+        }
+
+        if let Some(no_proxy) = call.get_flag_value("no-proxy") {
+            self.no_proxy = Some(no_proxy.into_string()?);
+        }
+
+        if let Some(timeout) = call.get_flag_value("connect-timeout") {
+            self.connect_timeout = Some(duration(&timeout)?);
+        }
+
+        if let Some(timeout) = call.get_flag_value("request-timeout") {
+            self.request_timeout = Some(duration(&timeout)?);
+        }
+
+        if call.has_flag("insecure-skip-verify")? {
+            self.insecure_skip_verify = true;
+        }
+
+        if let Some(server_name) = call.get_flag_value("tls-server-name") {
+            self.tls_server_name = Some(server_name.into_string()?);
+        }
+
+        if let Some(version) = call.get_flag_value("min-tls-version") {
+            self.min_tls_version = Some(tls_version(version.as_str()?, version.span())?);
+        }
+
+        Ok(())
+    }
+
+    /// Applies the options to `builder` for requests to `url`.
+    ///
+    /// A TLS server name connects directly to the host of `url`, so it cannot
+    /// be combined with a proxy.
+    pub fn apply(&self, builder: ClientBuilder, url: &str) -> Result<ClientBuilder, LabeledError> {
+        if self.tls_server_name.is_some() && self.proxy.is_some() {
+            return Err(LabeledError::new("Invalid TLS server name")
+                .with_help("tls_server_name cannot be used with a proxy"));
+        }
+
+        let builder = match (&self.proxy, &self.no_proxy) {
+            (None, None) => builder,
+            (None, Some(_)) => {
+                return Err(LabeledError::new("Invalid proxy configuration")
+                    .with_help("no_proxy requires a proxy"));
+            }
+            (Some(proxy), no_proxy) => {
+                let proxy = Proxy::all(proxy).map_err(|e| {
+                    LabeledError::new("Invalid proxy configuration")
+                        .with_help(format!("{proxy:?}: {e}"))
+                })?;
+
+                let proxy = proxy.no_proxy(no_proxy.as_deref().and_then(NoProxy::from_string));
+
+                builder.proxy(proxy)
+            }
+        };
+
+        let builder = match self.connect_timeout {
+            Some(timeout) => builder.connect_timeout(timeout),
+            None => builder,
+        };
+
+        let builder = match self.request_timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        };
+
+        let builder = if self.insecure_skip_verify {
+            builder
+                .tls_danger_accept_invalid_certs(true)
+                .tls_danger_accept_invalid_hostnames(true)
+        } else {
+            builder
+        };
+
+        let builder = match self.min_tls_version {
+            Some(version) => builder.tls_version_min(version),
+            None => builder,
+        };
+
+        let builder = match &self.tls_server_name {
+            Some(server_name) => builder.no_proxy().dns_resolver(ServerNameResolver {
+                server_name: server_name.clone(),
+                host: url_host(url)?,
+            }),
+            None => builder,
+        };
+
+        Ok(builder)
+    }
+
+    /// The URL to request for `url`.
+    ///
+    /// With a TLS server name the host is replaced by the server name, which
+    /// [`ClientOptions::apply`] resolves to the addresses of the original host,
+    /// so the server certificate is verified against the server name.  The
+    /// server name is also sent as the Host header.
+    pub fn request_url(&self, url: &str) -> Result<String, LabeledError> {
+        let Some(server_name) = &self.tls_server_name else {
+            return Ok(url.into());
+        };
+
+        let mut url = parse_url(url)?;
+
+        url.set_host(Some(server_name)).map_err(|e| {
+            LabeledError::new("Invalid TLS server name").with_help(format!("{server_name:?}: {e}"))
+        })?;
+
+        Ok(url.into())
+    }
+}
+
+fn duration_from_source(
+    source: &Record,
+    source_name: &str,
+    name: &str,
+) -> Result<Option<Duration>, LabeledError> {
+    source
+        .get(name)
+        .map(|value| {
+            if value.as_duration().is_err() {
+                return Err(
+                    LabeledError::new("Invalid plugin configuration").with_label(
+                        format!("Source {source_name:?} field {name:?} is not a duration"),
+                        value.span(),
+                    ),
+                );
+            }
+
+            duration(value)
+        })
+        .transpose()
+}
+
+fn duration(value: &Value) -> Result<Duration, LabeledError> {
+    let nanos = value.as_duration()?;
+
+    let nanos = u64::try_from(nanos).map_err(|_| {
+        LabeledError::new("Invalid timeout").with_label("must not be negative", value.span())
+    })?;
+
+    Ok(Duration::from_nanos(nanos))
+}
+
+fn parse_url(url: &str) -> Result<Url, LabeledError> {
+    Url::parse(url).map_err(|e| LabeledError::new("Invalid URL").with_help(format!("{url:?}: {e}")))
+}
+
+fn url_host(url: &str) -> Result<String, LabeledError> {
+    let url = parse_url(url)?;
+
+    let Some(host) = url.host_str() else {
+        return Err(LabeledError::new("Invalid URL").with_help(format!("{url} has no host")));
+    };
+
+    Ok(host.trim_start_matches('[').trim_end_matches(']').into())
+}
+
+/// Resolves a TLS server name to the addresses of the host of the source URL
+/// when connecting
+struct ServerNameResolver {
+    server_name: String,
+    host: String,
+}
+
+impl Resolve for ServerNameResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = if name.as_str() == self.server_name {
+            self.host.clone()
+        } else {
+            name.as_str().into()
+        };
+
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host, 0)).await?;
+
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+fn tls_version(version: &str, span: Span) -> Result<Version, LabeledError> {
+    let lower = version.to_ascii_lowercase();
+    let number = lower
+        .strip_prefix("tlsv")
+        .or_else(|| lower.strip_prefix("tls"))
+        .unwrap_or(&lower);
+
+    match number {
+        "1.0" => Ok(Version::TLS_1_0),
+        "1.1" => Ok(Version::TLS_1_1),
+        "1.2" => Ok(Version::TLS_1_2),
+        "1.3" => Ok(Version::TLS_1_3),
+        _ => Err(LabeledError::new("Invalid TLS version")
+            .with_label(format!("{version:?} must be 1.0, 1.1, 1.2, or 1.3"), span)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ClientOptions;
+    use nu_protocol::{Record, Span, Value, record};
+    use reqwest::{dns::Resolve, tls::Version};
+    use rstest::rstest;
+    use std::{net::SocketAddr, time::Duration};
+
+    #[test]
+    fn from_source() {
+        let source: Record = record! {
+            "url" => Value::test_string("https://prometheus.example/"),
+            "proxy" => Value::test_string("http://bastion.example:3128"),
+            "no_proxy" => Value::test_string("localhost"),
+            "connect_timeout" => Value::test_duration(5_000_000_000),
+            "request_timeout" => Value::test_duration(30_000_000_000),
+            "insecure_skip_verify" => Value::test_bool(true),
+            "tls_server_name" => Value::test_string("prometheus.internal"),
+            "min_tls_version" => Value::test_string("1.2"),
+        };
+
+        let options = ClientOptions::from_source(&source, "test").unwrap();
+
+        assert_eq!(
+            ClientOptions {
+                proxy: Some("http://bastion.example:3128".into()),
+                no_proxy: Some("localhost".into()),
+                connect_timeout: Some(Duration::from_secs(5)),
+                request_timeout: Some(Duration::from_secs(30)),
+                insecure_skip_verify: true,
+                tls_server_name: Some("prometheus.internal".into()),
+                min_tls_version: Some(Version::TLS_1_2),
+            },
+            options
+        );
+    }
+
+    #[test]
+    fn from_source_default() {
+        let source: Record = record! {
+            "url" => Value::test_string("https://prometheus.example/"),
+        };
+
+        let options = ClientOptions::from_source(&source, "test").unwrap();
+
+        assert_eq!(ClientOptions::default(), options);
+    }
+
+    #[test]
+    fn from_source_invalid_duration() {
+        let source: Record = record! {
+            "connect_timeout" => Value::test_int(5),
+        };
+
+        let err = ClientOptions::from_source(&source, "test").unwrap_err();
+
+        assert_eq!(
+            r#"Source "test" field "connect_timeout" is not a duration"#,
+            err.labels.first().unwrap().text
+        );
+    }
+
+    #[test]
+    fn apply_no_proxy_without_proxy() {
+        let options = ClientOptions {
+            no_proxy: Some("localhost".into()),
+            ..Default::default()
+        };
+
+        let err = options
+            .apply(reqwest::ClientBuilder::new(), "https://prometheus.example/")
+            .unwrap_err();
+
+        assert_eq!("Invalid proxy configuration", err.msg);
+    }
+
+    #[test]
+    fn apply_tls_server_name() {
+        let options = ClientOptions {
+            tls_server_name: Some("prometheus.internal".into()),
+            ..Default::default()
+        };
+
+        let builder = options
+            .apply(reqwest::ClientBuilder::new(), "https://127.0.0.1:9090/")
+            .unwrap();
+
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn apply_tls_server_name_proxy() {
+        let options = ClientOptions {
+            proxy: Some("http://bastion.example:3128".into()),
+            tls_server_name: Some("prometheus.internal".into()),
+            ..Default::default()
+        };
+
+        let err = options
+            .apply(reqwest::ClientBuilder::new(), "https://127.0.0.1:9090/")
+            .unwrap_err();
+
+        assert_eq!("Invalid TLS server name", err.msg);
+    }
+
+    #[test]
+    fn server_name_resolver() {
+        let resolver = super::ServerNameResolver {
+            server_name: "prometheus.internal".into(),
+            host: "127.0.0.1".into(),
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let addrs: Vec<_> = runtime
+            .block_on(resolver.resolve("prometheus.internal".parse().unwrap()))
+            .unwrap()
+            .collect();
+
+        assert_eq!(vec![SocketAddr::from(([127, 0, 0, 1], 0))], addrs);
+    }
+
+    #[rstest]
+    #[case("https://prometheus.example/", None, "https://prometheus.example/")]
+    #[case(
+        "https://10.0.0.1:9090/prometheus/",
+        Some("prometheus.internal"),
+        "https://prometheus.internal:9090/prometheus/"
+    )]
+    fn request_url(
+        #[case] url: &str,
+        #[case] tls_server_name: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let options = ClientOptions {
+            tls_server_name: tls_server_name.map(String::from),
+            ..Default::default()
+        };
+
+        assert_eq!(expected, options.request_url(url).unwrap());
+    }
+
+    #[rstest]
+    #[case("1.2", Version::TLS_1_2)]
+    #[case("TLSv1.3", Version::TLS_1_3)]
+    #[case("tls1.0", Version::TLS_1_0)]
+    fn tls_version(#[case] version: &str, #[case] expected: Version) {
+        assert_eq!(
+            expected,
+            super::tls_version(version, Span::test_data()).unwrap()
+        );
+    }
+
+    #[test]
+    fn tls_version_invalid() {
+        let err = super::tls_version("1.4", Span::test_data()).unwrap_err();
+
+        assert_eq!("Invalid TLS version", err.msg);
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{digit1, one_of},
+    combinator::{all_consuming, map_opt, map_res, opt},
+    multi::many1,
+};
+use nu_plugin::EvaluatedCall;
+use nu_protocol::{LabeledError, Span, SyntaxShape, Value};
+
+/// Points per series for a range query without a step, as in the Prometheus UI
+pub const DEFAULT_POINTS: u32 = 250;
+
+/// Shape of a time flag: a datetime, a duration relative to now, or a string
+/// such as `now-15m`
+pub fn time_shape() -> SyntaxShape {
+    SyntaxShape::OneOf(vec![
+        SyntaxShape::DateTime,
+        SyntaxShape::Duration,
+        SyntaxShape::String,
+    ])
+}
+
+/// Reads the time flag `name` relative to the current time
+pub fn time_flag(
+    call: &EvaluatedCall,
+    name: &str,
+) -> Result<Option<DateTime<FixedOffset>>, LabeledError> {
+    let now = Utc::now().fixed_offset();
+
+    call.get_flag_value(name)
+        .map(|value| time_from_value(&value, now))
+        .transpose()
+}
+
+/// Converts a time flag value to a time.
+///
+/// A duration is an offset from `now`, so `-1hr` is one hour ago.  A string
+/// may be `now` with an optional Prometheus duration offset like `now-15m`,
+/// an RFC 3339 time, or a unix timestamp in seconds.
+pub fn time_from_value(
+    value: &Value,
+    now: DateTime<FixedOffset>,
+) -> Result<DateTime<FixedOffset>, LabeledError> {
+    let span = value.span();
+
+    match value {
+        Value::Date { val, .. } => Ok(*val),
+        Value::Duration { val, .. } => Ok(now + TimeDelta::nanoseconds(*val)),
+        Value::String { val, .. } => parse_time(val, now).ok_or_else(|| {
+            LabeledError::new("Invalid time")
+                .with_label(format!("unable to parse {val:?}"), span)
+                .with_help(
+                    "Use a datetime, a duration relative to now like -1hr, now-15m, an RFC 3339 time, or a unix timestamp",
+                )
+        }),
+        _ => Err(LabeledError::new("Invalid time")
+            .with_label("must be a datetime, duration, or string", span)),
+    }
+}
+
+/// Step width in seconds giving about `points` samples per series between
+/// `start` and `end`.
+///
+/// Like the Prometheus UI the step is rounded up to whole seconds.
+pub fn auto_step(start: DateTime<FixedOffset>, end: DateTime<FixedOffset>, points: u32) -> f64 {
+    let span = (end - start).num_milliseconds() as f64 / 1000.0;
+
+    (span / points as f64).ceil().max(1.0)
+}
+
+/// Converts an RFC 3339 time returned by the API to a datetime value.
+///
+/// A time that does not parse is returned as a string.
+pub fn rfc3339_to_value(time: &str, span: Span) -> Value {
+    match DateTime::parse_from_rfc3339(time) {
+        Ok(time) => Value::date(time, span),
+        Err(_) => Value::string(time, span),
+    }
+}
+
+fn parse_time(input: &str, now: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let input = input.trim();
+
+    if let Ok((_, offset)) = all_consuming(relative).parse(input) {
+        return Some(now + offset);
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time);
+    }
+
+    let seconds: f64 = input.parse().ok()?;
+
+    if !seconds.is_finite() {
+        return None;
+    }
+
+    // microseconds keep the timestamp within f64 precision
+    let micros = (seconds * 1_000_000.0).round() as i64;
+
+    DateTime::from_timestamp_micros(micros).map(|time| time.fixed_offset())
+}
+
+fn relative(input: &str) -> IResult<&str, TimeDelta> {
+    let (input, _) = tag("now").parse(input)?;
+    let (input, offset) = opt((one_of("+-"), duration)).parse(input)?;
+
+    let offset = match offset {
+        Some(('-', duration)) => -duration,
+        Some((_, duration)) => duration,
+        None => TimeDelta::zero(),
+    };
+
+    Ok((input, offset))
+}
+
+fn duration(input: &str) -> IResult<&str, TimeDelta> {
+    map_opt(
+        many1((
+            map_res(digit1, str::parse::<i32>),
+            alt((
+                tag("ms"),
+                tag("s"),
+                tag("m"),
+                tag("h"),
+                tag("d"),
+                tag("w"),
+                tag("y"),
+            )),
+        )),
+        |parts| {
+            parts
+                .into_iter()
+                .try_fold(TimeDelta::zero(), |total, (count, unit)| {
+                    unit_duration(unit)
+                        .checked_mul(count)
+                        .and_then(|duration| total.checked_add(&duration))
+                })
+        },
+    )
+    .parse(input)
+}
+
+fn unit_duration(unit: &str) -> TimeDelta {
+    match unit {
+        "ms" => TimeDelta::milliseconds(1),
+        "s" => TimeDelta::seconds(1),
+        "m" => TimeDelta::minutes(1),
+        "h" => TimeDelta::hours(1),
+        "d" => TimeDelta::days(1),
+        "w" => TimeDelta::weeks(1),
+        "y" => TimeDelta::days(365),
+        _ => unreachable!("unit {unit} not matched by parser"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, FixedOffset, TimeDelta};
+    use nom::{Parser, combinator::all_consuming};
+    use nu_protocol::{Span, Value};
+    use rstest::rstest;
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2024-05-19T05:20:32.5Z").unwrap()
+    }
+
+    #[rstest]
+    #[case("15m", Some(TimeDelta::minutes(15)))]
+    #[case("1h30m", Some(TimeDelta::minutes(90)))]
+    #[case("500ms", Some(TimeDelta::milliseconds(500)))]
+    #[case("1w2d", Some(TimeDelta::days(9)))]
+    #[case("1y", Some(TimeDelta::days(365)))]
+    #[case("1h5", None)]
+    #[case("", None)]
+    #[case("m", None)]
+    #[case("99999999999y", None)]
+    fn duration(#[case] input: &str, #[case] expected: Option<TimeDelta>) {
+        let duration = all_consuming(super::duration)
+            .parse(input)
+            .ok()
+            .map(|(_, duration)| duration);
+
+        assert_eq!(expected, duration);
+    }
+
+    #[rstest]
+    #[case(TimeDelta::hours(1), 250, 15.0)]
+    #[case(TimeDelta::weeks(1), 250, 2420.0)]
+    #[case(TimeDelta::minutes(1), 250, 1.0)]
+    #[case(TimeDelta::zero(), 250, 1.0)]
+    #[case(TimeDelta::hours(1), 60, 60.0)]
+    fn auto_step(#[case] span: TimeDelta, #[case] points: u32, #[case] expected: f64) {
+        assert_eq!(expected, super::auto_step(now() - span, now(), points));
+    }
+
+    #[rstest]
+    #[case("now", "2024-05-19T05:20:32.5Z")]
+    #[case("now-15m", "2024-05-19T05:05:32.5Z")]
+    #[case("now+1h", "2024-05-19T06:20:32.5Z")]
+    #[case("2024-01-01T00:00:00.25Z", "2024-01-01T00:00:00.25Z")]
+    #[case("1716096032.125", "2024-05-19T05:20:32.125Z")]
+    fn time_from_string(#[case] input: &str, #[case] expected: &str) {
+        let expected = DateTime::parse_from_rfc3339(expected).unwrap();
+
+        let time = super::time_from_value(&Value::test_string(input), now()).unwrap();
+
+        assert_eq!(expected, time);
+    }
+
+    #[test]
+    fn rfc3339_to_value() {
+        let value = super::rfc3339_to_value("2024-05-19T05:20:32.5Z", Span::test_data());
+
+        assert_eq!(now(), value.as_date().unwrap());
+
+        let value = super::rfc3339_to_value("never", Span::test_data());
+
+        assert_eq!("never", value.as_str().unwrap());
+    }
+
+    #[rstest]
+    #[case("now-")]
+    #[case("yesterday")]
+    #[case("NaN")]
+    fn time_from_string_invalid(#[case] input: &str) {
+        let err = super::time_from_value(&Value::test_string(input), now()).unwrap_err();
+
+        assert_eq!("Invalid time", err.msg);
+    }
+
+    #[test]
+    fn time_from_duration() {
+        let value = Value::duration(-3_600_000_000_000, Span::test_data());
+
+        let time = super::time_from_value(&value, now()).unwrap();
+
+        assert_eq!(now() - TimeDelta::hours(1), time);
+    }
+
+    #[test]
+    fn time_from_date() {
+        let value = Value::test_date(now());
+
+        assert_eq!(now(), super::time_from_value(&value, now()).unwrap());
+    }
+}